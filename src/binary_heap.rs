@@ -0,0 +1,347 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::slice;
+
+use crate::fixed::{Full, StaticMinHeap};
+
+/// The ordering of a `BinaryHeap`, either `Min` or `Max`.
+///
+/// This trait is sealed and can't be implemented outside the crate.
+pub trait Kind: private::Sealed {
+    #[doc(hidden)]
+    fn above<T: Ord>(a: &T, b: &T) -> bool;
+}
+
+/// Keeps the smallest value at the top of the heap.
+pub enum Min {}
+
+/// Keeps the largest value at the top of the heap.
+pub enum Max {}
+
+impl Kind for Min {
+    fn above<T: Ord>(a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+impl Kind for Max {
+    fn above<T: Ord>(a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Min {}
+    impl Sealed for super::Max {}
+}
+
+/// A fixed-capacity binary heap of `N` values, ordered by `K`.
+///
+/// This mirrors the API of `heapless::binary_heap::BinaryHeap`, so code
+/// written against it can switch over by changing its imports. The
+/// values are kept in the same inline storage as `StaticMinHeap`.
+pub struct BinaryHeap<T, K, const N: usize> {
+    heap: StaticMinHeap<T, N>,
+    kind: PhantomData<K>,
+}
+
+impl<T, K, const N: usize> BinaryHeap<T, K, N> {
+    /// creates an empty heap
+    /// # Example
+    /// ```
+    /// use min_heap::binary_heap::{BinaryHeap, Max};
+    ///
+    /// static HEAP: BinaryHeap<i32, Max, 8> = BinaryHeap::new();
+    /// assert!(HEAP.is_empty())
+    /// ```
+    pub const fn new() -> Self {
+        BinaryHeap {
+            heap: StaticMinHeap::new(),
+            kind: PhantomData,
+        }
+    }
+
+    /// returns the number of values the heap can hold
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// returns the number of values in the heap
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// returns true if the heap holds no values
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// removes every value from the heap
+    pub fn clear(&mut self) {
+        self.heap.clear()
+    }
+
+    /// returns an iterator over the values in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.heap.as_slice().iter()
+    }
+}
+
+impl<T: Ord, K: Kind, const N: usize> BinaryHeap<T, K, N> {
+    /// returns the top value without removing it: the smallest for `Min`,
+    /// the largest for `Max`
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    /// adds a value to the heap, or gives it back if the heap is full
+    /// # Example
+    /// ```
+    /// use min_heap::binary_heap::{BinaryHeap, Max};
+    ///
+    /// let mut heap: BinaryHeap<i32, Max, 2> = BinaryHeap::new();
+    /// assert_eq!(heap.push(1), Ok(()));
+    /// assert_eq!(heap.push(3), Ok(()));
+    /// assert_eq!(heap.push(2), Err(2));
+    /// assert_eq!(heap.peek(), Some(&3))
+    /// ```
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        self.heap
+            .push_by(item, &K::above)
+            .map_err(|Full(item)| item)
+    }
+
+    /// removes and returns the top value, or `None` if the heap is empty
+    /// # Example
+    /// ```
+    /// use min_heap::binary_heap::{BinaryHeap, Min};
+    ///
+    /// let mut heap: BinaryHeap<i32, Min, 4> = BinaryHeap::new();
+    /// heap.push(7).unwrap();
+    /// heap.push(4).unwrap();
+    /// assert_eq!(heap.pop(), Some(4))
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop_by(&K::above)
+    }
+}
+
+impl<T, K, const N: usize> Default for BinaryHeap<T, K, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, K, const N: usize> fmt::Debug for BinaryHeap<T, K, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, K, const N: usize> IntoIterator for &'a BinaryHeap<T, K, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The order a `DirectedHeap` pops its values in, chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// smallest first, like `BinaryHeap<T, Min, N>`
+    Ascending,
+    /// largest first, like `BinaryHeap<T, Max, N>`
+    Descending,
+}
+
+/// A fixed-capacity binary heap of `N` values whose direction is picked
+/// when it is created rather than by a type parameter.
+///
+/// This lets one code path serve both an "ascending" and a "descending"
+/// setting. Each comparison checks the direction, which `BinaryHeap`
+/// avoids by fixing it at compile time.
+/// # Example
+/// ```
+/// use min_heap::binary_heap::{DirectedHeap, Direction};
+///
+/// fn top(values: &[i32], direction: Direction) -> Option<i32> {
+///     let mut heap: DirectedHeap<i32, 8> = DirectedHeap::new(direction);
+///     for value in values {
+///         heap.push(*value).unwrap();
+///     }
+///     heap.pop()
+/// }
+///
+/// assert_eq!(top(&[3, 1, 2], Direction::Ascending), Some(1));
+/// assert_eq!(top(&[3, 1, 2], Direction::Descending), Some(3))
+/// ```
+pub struct DirectedHeap<T, const N: usize> {
+    heap: StaticMinHeap<T, N>,
+    direction: Direction,
+}
+
+impl<T, const N: usize> DirectedHeap<T, N> {
+    /// creates an empty heap that pops in the given direction
+    pub const fn new(direction: Direction) -> Self {
+        DirectedHeap {
+            heap: StaticMinHeap::new(),
+            direction,
+        }
+    }
+
+    /// returns the direction the heap pops in
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// returns the number of values the heap can hold
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// returns the number of values in the heap
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// returns true if the heap holds no values
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// removes every value from the heap
+    pub fn clear(&mut self) {
+        self.heap.clear()
+    }
+
+    /// returns an iterator over the values in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.heap.as_slice().iter()
+    }
+}
+
+impl<T: Ord, const N: usize> DirectedHeap<T, N> {
+    /// returns the value that would be popped next without removing it
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    /// adds a value to the heap, or gives it back if the heap is full
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let direction = self.direction;
+        self.heap
+            .push_by(item, &|a: &T, b: &T| above(direction, a, b))
+            .map_err(|Full(item)| item)
+    }
+
+    /// removes and returns the next value in the heap's direction, or
+    /// `None` if the heap is empty
+    pub fn pop(&mut self) -> Option<T> {
+        let direction = self.direction;
+        self.heap.pop_by(&|a: &T, b: &T| above(direction, a, b))
+    }
+}
+
+// the `above` ordering the sift helpers expect, for either direction
+fn above<T: Ord>(direction: Direction, a: &T, b: &T) -> bool {
+    match direction {
+        Direction::Ascending => Min::above(a, b),
+        Direction::Descending => Max::above(a, b),
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for DirectedHeap<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DirectedHeap")
+            .field("direction", &self.direction)
+            .field("values", &self.heap.as_slice())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain<K: Kind, const N: usize>(heap: &mut BinaryHeap<i32, K, N>) -> Vec<i32> {
+        let mut popped = vec![];
+        while let Some(value) = heap.pop() {
+            popped.push(value);
+        }
+        popped
+    }
+
+    #[test]
+    fn min_kind_pops_ascending() {
+        let mut heap: BinaryHeap<i32, Min, 8> = BinaryHeap::new();
+        for value in [5, 1, 4, 2, 3].iter() {
+            heap.push(*value).unwrap();
+        }
+        assert_eq!(drain(&mut heap), vec![1, 2, 3, 4, 5])
+    }
+
+    #[test]
+    fn max_kind_pops_descending() {
+        let mut heap: BinaryHeap<i32, Max, 8> = BinaryHeap::new();
+        for value in [5, 1, 4, 2, 3].iter() {
+            heap.push(*value).unwrap();
+        }
+        assert_eq!(drain(&mut heap), vec![5, 4, 3, 2, 1])
+    }
+
+    #[test]
+    fn push_gives_back_value_when_full() {
+        let mut heap: BinaryHeap<i32, Min, 1> = BinaryHeap::new();
+        heap.push(1).unwrap();
+        assert_eq!(heap.push(0), Err(0));
+        assert_eq!(heap.len(), 1)
+    }
+
+    #[test]
+    fn iter_and_clear() {
+        let mut heap: BinaryHeap<i32, Max, 4> = BinaryHeap::new();
+        heap.push(2).unwrap();
+        heap.push(9).unwrap();
+
+        let mut values: Vec<i32> = heap.iter().cloned().collect();
+        values.sort();
+        assert_eq!(values, vec![2, 9]);
+
+        heap.clear();
+        assert!(heap.is_empty());
+        assert_eq!(heap.capacity(), 4)
+    }
+
+    #[test]
+    fn directed_heap_follows_direction() {
+        for &(direction, expected) in [
+            (Direction::Ascending, [1, 2, 3, 4, 5]),
+            (Direction::Descending, [5, 4, 3, 2, 1]),
+        ].iter() {
+            let mut heap: DirectedHeap<i32, 8> = DirectedHeap::new(direction);
+            for value in [5, 1, 4, 2, 3].iter() {
+                heap.push(*value).unwrap();
+            }
+            assert_eq!(heap.direction(), direction);
+            assert_eq!(heap.peek(), Some(&expected[0]));
+
+            let mut popped = vec![];
+            while let Some(value) = heap.pop() {
+                popped.push(value);
+            }
+            assert_eq!(popped, expected)
+        }
+    }
+
+    #[test]
+    fn directed_heap_gives_back_value_when_full() {
+        let mut heap: DirectedHeap<i32, 1> = DirectedHeap::new(Direction::Descending);
+        heap.push(1).unwrap();
+        assert_eq!(heap.push(2), Err(2));
+        assert_eq!(heap.peek(), Some(&1))
+    }
+}
@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::node::Node;
+
+#[cfg(not(feature = "sync-nodes"))]
+use std::rc::Rc;
+#[cfg(feature = "sync-nodes")]
+use std::sync::Arc as Rc;
+
+/// The nodes that were still alive after `leak_check` dropped the tree,
+/// identified by their values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    /// the values of the nodes that are still alive
+    pub values: Vec<i32>,
+}
+
+impl fmt::Display for Leak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} node(s) outlived their tree: {:?}", self.values.len(), self.values)
+    }
+}
+
+impl Error for Leak {}
+
+/// drops a tree and checks that every one of its nodes was freed
+///
+/// `root` should be the last reference to the top of the tree. Any node
+/// that survives is being kept alive from somewhere else, such as a
+/// stray `Rc` or a reference cycle, and is reported in the returned
+/// `Leak`.
+/// # Example
+/// ```
+/// use min_heap::node::Node;
+///
+/// let root = Node::new_orphan(1);
+/// Node::new_child(&root, 2).unwrap();
+/// assert_eq!(min_heap::debug::leak_check(root), Ok(()))
+/// ```
+pub fn leak_check(root: Rc<Node>) -> Result<(), Leak> {
+    let nodes = Node::downgrade_all(&root);
+    drop(root);
+
+    let values: Vec<i32> = nodes
+        .iter()
+        .filter_map(|node| node.upgrade())
+        .map(|node| node.get_value())
+        .collect();
+
+    if values.is_empty() {
+        Ok(())
+    } else {
+        Err(Leak { values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn released_tree_passes() {
+        let root = Node::new_orphan(1);
+        let child = Node::new_orphan(2);
+        Node::parent_child(&root, &child).unwrap();
+        Node::new_child(&child, 3).unwrap();
+        drop(child);
+
+        assert_eq!(leak_check(root), Ok(()))
+    }
+
+    // a reference held outside the tree keeps that node alive
+    #[test]
+    fn stray_reference_is_reported() {
+        let root = Node::new_orphan(1);
+        let child = Node::new_orphan(2);
+        Node::parent_child(&root, &child).unwrap();
+        Node::new_child(&root, 3).unwrap();
+
+        assert_eq!(leak_check(root), Err(Leak { values: vec![2] }));
+        assert_eq!(Node::strong_count(&child), 1)
+    }
+}
@@ -0,0 +1,149 @@
+use core::fmt;
+use core::ops::Add;
+
+use crate::fixed::Full;
+use crate::priority::PriorityHeap;
+
+/// A queue of up to `N` key/value entries, each with a time to live,
+/// ordered by when they expire.
+///
+/// Times are whatever clock the caller uses, as long as adding a time to
+/// live `D` to a time `T` gives the deadline: `Instant` and `Duration`,
+/// or plain tick counts. The queue never reads a clock itself.
+///
+/// Like the heap it is built on, its capacity is fixed. An insert into a
+/// full queue fails, so a cache using it as its eviction order has to
+/// purge, or drop something itself, before it can admit more entries.
+/// # Example
+/// ```
+/// use min_heap::expiry::ExpiryQueue;
+///
+/// let mut queue: ExpiryQueue<u64, &str, u32, 8> = ExpiryQueue::new();
+/// queue.insert("session", 1, 0, 30).unwrap();
+/// queue.insert("token", 2, 0, 10).unwrap();
+///
+/// let mut evicted = vec![];
+/// assert_eq!(queue.purge_expired(&15, |key, _| evicted.push(key)), 1);
+/// assert_eq!(evicted, vec!["token"]);
+/// assert_eq!(queue.next_deadline(), Some(&30))
+/// ```
+pub struct ExpiryQueue<T, K, V, const N: usize> {
+    entries: PriorityHeap<T, (K, V), N>,
+}
+
+impl<T, K, V, const N: usize> ExpiryQueue<T, K, V, N> {
+    /// creates an empty queue with room for `N` entries
+    pub const fn new() -> Self {
+        ExpiryQueue { entries: PriorityHeap::new() }
+    }
+
+    /// returns the number of entries that have not been purged
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// returns true if the queue holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// returns the number of entries the queue can hold, which is always `N`
+    pub fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T: Ord, K, V, const N: usize> ExpiryQueue<T, K, V, N> {
+    /// adds an entry that expires `ttl` after `now`, or gives the key and
+    /// value back in `Err(Full)` if the queue has no room left
+    pub fn insert<D>(&mut self, key: K, value: V, now: T, ttl: D) -> Result<(), Full<(K, V)>>
+    where
+        T: Add<D, Output = T>,
+    {
+        self.entries
+            .push(now + ttl, (key, value))
+            .map_err(|Full((_, entry))| Full(entry))
+    }
+
+    /// returns the earliest deadline in the queue, if there is one
+    pub fn next_deadline(&self) -> Option<&T> {
+        self.entries.peek().map(|(deadline, _)| deadline)
+    }
+
+    /// removes every entry whose deadline is at or before `now`, earliest
+    /// first, handing each one to `on_expired`, and returns how many were
+    /// removed
+    pub fn purge_expired<F: FnMut(K, V)>(&mut self, now: &T, mut on_expired: F) -> usize {
+        let mut purged = 0;
+        while let Some((_, (key, value))) = self.entries.pop_if_le(now) {
+            on_expired(key, value);
+            purged += 1;
+        }
+        purged
+    }
+}
+
+impl<T, K, V, const N: usize> Default for ExpiryQueue<T, K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, K: fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for ExpiryQueue<T, K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.entries, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purges_in_deadline_order() {
+        let mut queue: ExpiryQueue<u64, char, (), 8> = ExpiryQueue::new();
+        queue.insert('c', (), 5, 20).unwrap();
+        queue.insert('a', (), 0, 10).unwrap();
+        queue.insert('b', (), 10, 5).unwrap();
+        queue.insert('d', (), 0, 100).unwrap();
+
+        let mut purged = vec![];
+        assert_eq!(queue.purge_expired(&9, |key, _| purged.push(key)), 0);
+        assert_eq!(queue.purge_expired(&25, |key, _| purged.push(key)), 3);
+        assert_eq!(purged, vec!['a', 'b', 'c']);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.next_deadline(), Some(&100))
+    }
+
+    // an entry whose deadline is exactly now has expired
+    #[test]
+    fn deadline_is_inclusive() {
+        let mut queue: ExpiryQueue<u32, &str, &str, 1> = ExpiryQueue::new();
+        queue.insert("k", "v", 3, 4).unwrap();
+
+        let mut purged = vec![];
+        assert_eq!(queue.purge_expired(&7, |key, value| purged.push((key, value))), 1);
+        assert_eq!(purged, vec![("k", "v")]);
+        assert!(queue.is_empty())
+    }
+
+    #[test]
+    fn full_queue_gives_back_entry() {
+        let mut queue: ExpiryQueue<u32, &str, i32, 1> = ExpiryQueue::new();
+        queue.insert("kept", 1, 0, 1).unwrap();
+        assert_eq!(queue.insert("rejected", 2, 0, 1), Err(Full(("rejected", 2))));
+        assert_eq!(queue.len(), 1)
+    }
+
+    #[test]
+    fn works_with_instants() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let mut queue: ExpiryQueue<Instant, u8, (), 2> = ExpiryQueue::new();
+        queue.insert(1, (), start, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(queue.purge_expired(&start, |_, _| ()), 0);
+        assert_eq!(queue.purge_expired(&(start + Duration::from_secs(60)), |_, _| ()), 1)
+    }
+}
@@ -0,0 +1,1033 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::slice;
+
+use crate::sift::{sift_down, sift_up};
+
+/// The error returned when pushing onto a `StaticMinHeap` that is already
+/// full. It hands the rejected value back to the caller.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Full<T>(pub T);
+
+impl<T> fmt::Display for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "heap is full")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for Full<T> {}
+
+/// A min-heap with room for exactly `N` values, stored inline.
+///
+/// The values live in a `[MaybeUninit<T>; N]` array laid out as an
+/// implicit binary tree, so the heap never allocates. Only `core`
+/// functionality is used, so it is still available when the crate is
+/// built as `no_std` with `default-features = false`.
+///
+/// This makes it safe to use from real-time threads, such as audio
+/// callbacks: `push`, `pop`, `peek`, `pop_if_le` and `pop_batch` never
+/// call the allocator. Their cost is bounded by the depth of the tree,
+/// whatever the order of the values. The only memory freed is what the
+/// values themselves own, when they are dropped.
+pub struct StaticMinHeap<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T: Ord, const N: usize> StaticMinHeap<T, N> {
+    /// adds a value to the heap, or gives it back in `Err(Full)` if the
+    /// heap has no room left
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::{Full, StaticMinHeap};
+    ///
+    /// let mut heap = StaticMinHeap::<i32, 1>::new();
+    /// assert_eq!(heap.push(4), Ok(()));
+    /// assert_eq!(heap.push(2), Err(Full(2)))
+    /// ```
+    pub fn push(&mut self, value: T) -> Result<(), Full<T>> {
+        self.push_by(value, &less)
+    }
+
+    /// removes and returns the smallest value, or `None` if the heap is empty
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(5).unwrap();
+    /// heap.push(3).unwrap();
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.pop(), Some(5));
+    /// assert_eq!(heap.pop(), None)
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.pop_by(&less)
+    }
+
+    /// returns a lazy iterator that pops up to `max_n` of the smallest
+    /// values as it is consumed, in ascending order, stopping early at the
+    /// first value that doesn't satisfy `pred`
+    ///
+    /// Nothing is removed until the iterator is driven: the value that
+    /// stopped the batch stays in the heap, as does anything the iterator
+    /// wasn't driven far enough to take. Like the heap itself, a batch
+    /// never allocates.
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 8>::new();
+    /// for value in [4, 1, 9, 2, 7].iter() {
+    ///     heap.push(*value).unwrap();
+    /// }
+    ///
+    /// assert_eq!(heap.pop_batch(2, |_| true).collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(heap.pop_batch(8, |v| *v < 8).collect::<Vec<_>>(), vec![4, 7]);
+    /// assert_eq!(heap.peek(), Some(&9))
+    /// ```
+    #[must_use = "the batch is only popped as the iterator is consumed"]
+    pub fn pop_batch<P: FnMut(&T) -> bool>(&mut self, max_n: usize, pred: P) -> PopBatch<'_, T, P, N> {
+        PopBatch { heap: self, remaining: max_n, pred }
+    }
+
+    /// removes and returns the smallest value only if it is no greater
+    /// than `bound`, such as popping only the tasks whose scheduled time
+    /// has arrived
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(10).unwrap();
+    /// heap.push(20).unwrap();
+    ///
+    /// assert_eq!(heap.pop_if_le(&15), Some(10));
+    /// assert_eq!(heap.pop_if_le(&15), None);
+    /// assert_eq!(heap.len(), 1)
+    /// ```
+    pub fn pop_if_le(&mut self, bound: &T) -> Option<T> {
+        match self.peek() {
+            Some(min) if min <= bound => self.pop(),
+            _ => None,
+        }
+    }
+
+    /// turns the heap into an iterator that pops one value on each call to
+    /// `next`, so the values come out in ascending order without ever
+    /// being collected into a sorted buffer
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(3).unwrap();
+    /// heap.push(1).unwrap();
+    /// heap.push(2).unwrap();
+    ///
+    /// let mut sorted = heap.into_sorted_iter();
+    /// assert_eq!(sorted.next(), Some(1));
+    /// assert_eq!(sorted.len(), 2);
+    /// assert_eq!(sorted.collect::<Vec<_>>(), vec![2, 3])
+    /// ```
+    pub fn into_sorted_iter(self) -> IntoSortedIter<T, N> {
+        IntoSortedIter(self)
+    }
+
+    /// removes and returns the smallest value without checking that the
+    /// heap has one
+    ///
+    /// # Safety
+    /// The heap must not be empty. Debug builds assert this.
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(2).unwrap();
+    /// if !heap.is_empty() {
+    ///     assert_eq!(unsafe { heap.pop_unchecked() }, 2);
+    /// }
+    /// ```
+    pub unsafe fn pop_unchecked(&mut self) -> T {
+        self.pop_by_unchecked(&less)
+    }
+}
+
+impl<T, const N: usize> StaticMinHeap<T, N> {
+    /// creates an empty heap with room for `N` values
+    ///
+    /// This is a `const fn`, so a heap can be the initial value of a
+    /// `static` without any lazy initialization.
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::StaticMinHeap;
+    ///
+    /// static EMPTY: StaticMinHeap<i32, 8> = StaticMinHeap::new();
+    /// assert!(EMPTY.is_empty());
+    ///
+    /// let heap = StaticMinHeap::<i32, 8>::new();
+    /// assert!(heap.is_empty())
+    /// ```
+    pub const fn new() -> Self {
+        StaticMinHeap {
+            // an array of `MaybeUninit`s does not need initializing
+            data: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+        }
+    }
+
+    /// returns the smallest value without removing it
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(9).unwrap();
+    /// heap.push(6).unwrap();
+    /// assert_eq!(heap.peek(), Some(&6))
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    /// returns the smallest value without checking that the heap has one
+    ///
+    /// # Safety
+    /// The heap must not be empty. Debug builds assert this.
+    pub unsafe fn peek_unchecked(&self) -> &T {
+        self.get_unchecked(0)
+    }
+
+    /// returns the value stored at `index` in the heap's array layout,
+    /// where the children of `i` are at `2 * i + 1` and `2 * i + 2`
+    ///
+    /// # Safety
+    /// `index` must be less than `len()`. Debug builds assert this.
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(5).unwrap();
+    /// heap.push(1).unwrap();
+    /// for i in 0..heap.len() {
+    ///     let value = unsafe { heap.get_unchecked(i) };
+    ///     assert!(*value >= 1);
+    /// }
+    /// ```
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T {
+        debug_assert!(index < self.len);
+        &*self.data.get_unchecked(index).as_ptr()
+    }
+
+    /// returns the number of values in the heap
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// returns true if the heap holds no values
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// returns true if another push would fail
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// returns the number of values the heap can hold, which is always `N`
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// removes every value from the heap
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::fixed::StaticMinHeap::<i32, 4>::new();
+    /// heap.push(1).unwrap();
+    /// heap.clear();
+    /// assert!(heap.is_empty())
+    /// ```
+    pub fn clear(&mut self) {
+        let values = self.as_mut_slice() as *mut [T];
+        // forget the values before dropping them, so a panicking drop
+        // can't lead to them being dropped again
+        self.len = 0;
+        unsafe { ptr::drop_in_place(values) }
+    }
+
+    // the push and pop logic with the ordering left to the caller, where
+    // `above(a, b)` is true when `a` belongs higher up the heap than `b`
+    pub(crate) fn push_by<F: Fn(&T, &T) -> bool>(&mut self, value: T, above: &F) -> Result<(), Full<T>> {
+        if self.len == N {
+            return Err(Full(value));
+        }
+
+        self.data[self.len] = MaybeUninit::new(value);
+        self.len += 1;
+
+        let last = self.len - 1;
+        sift_up(self.as_mut_slice(), last, above);
+        Ok(())
+    }
+
+    pub(crate) fn pop_by<F: Fn(&T, &T) -> bool>(&mut self, above: &F) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(unsafe { self.pop_by_unchecked(above) })
+    }
+
+    // the caller makes sure the heap is not empty
+    unsafe fn pop_by_unchecked<F: Fn(&T, &T) -> bool>(&mut self, above: &F) -> T {
+        debug_assert!(self.len > 0);
+
+        // move the root to the end, then shrink the heap past it
+        let last = self.len - 1;
+        self.as_mut_slice().swap(0, last);
+        self.len = last;
+        let value = ptr::read(self.data.get_unchecked(last).as_ptr());
+
+        sift_down(self.as_mut_slice(), 0, above);
+        value
+    }
+
+    pub(crate) fn as_slice(&self) -> &[T] {
+        // the first `len` slots are always initialized
+        unsafe { slice::from_raw_parts(self.data.as_ptr() as *const T, self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T, const N: usize> Default for StaticMinHeap<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for StaticMinHeap<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for StaticMinHeap<T, N> {
+    fn clone(&self) -> Self {
+        let mut heap = StaticMinHeap::new();
+        // copying slot by slot keeps the same layout, and counting as we
+        // go means a panicking clone only drops what was copied
+        for value in self.as_slice() {
+            heap.data[heap.len] = MaybeUninit::new(value.clone());
+            heap.len += 1;
+        }
+        heap
+    }
+}
+
+impl<T: Ord + Clone, const N: usize> StaticMinHeap<T, N> {
+    // the values in ascending order, popped from a copy of the heap so
+    // that no allocation is needed
+    fn sorted(&self) -> IntoSortedIter<T, N> {
+        self.clone().into_sorted_iter()
+    }
+
+    /// compares the two heaps as multisets, splitting their values into
+    /// those only in this heap, those only in `other`, and those in both
+    ///
+    /// A value held twice here and once in `other` counts once as common
+    /// and once as only in this heap. Both heaps are walked in ascending
+    /// order together, popping from copies, so nothing is allocated.
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::StaticMinHeap;
+    ///
+    /// let mut ours = StaticMinHeap::<i32, 4>::new();
+    /// let mut theirs = StaticMinHeap::<i32, 4>::new();
+    /// for value in [1, 2, 2, 5].iter() {
+    ///     ours.push(*value).unwrap();
+    /// }
+    /// for value in [2, 3, 5].iter() {
+    ///     theirs.push(*value).unwrap();
+    /// }
+    ///
+    /// let diff = ours.diff(&theirs);
+    /// assert_eq!(diff.only_self.into_sorted_iter().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(diff.only_other.into_sorted_iter().collect::<Vec<_>>(), vec![3]);
+    /// assert_eq!(diff.common.into_sorted_iter().collect::<Vec<_>>(), vec![2, 5])
+    /// ```
+    pub fn diff(&self, other: &Self) -> Diff<T, N> {
+        let mut diff = Diff {
+            only_self: StaticMinHeap::new(),
+            only_other: StaticMinHeap::new(),
+            common: StaticMinHeap::new(),
+        };
+        self.merge_sides(other, |side, value| {
+            let part = match side {
+                Side::OnlySelf => &mut diff.only_self,
+                Side::OnlyOther => &mut diff.only_other,
+                Side::Both => &mut diff.common,
+            };
+            // no part can hold more than one of the heaps did
+            part.push(value).ok().expect("a part of a diff always fits in N");
+        });
+        diff
+    }
+
+    /// builds the multiset union of the two heaps, holding each value as
+    /// many times as whichever heap holds it more often, or returns the
+    /// first value that didn't fit in `Err(Full)`
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::{Full, StaticMinHeap};
+    ///
+    /// let mut a = StaticMinHeap::<i32, 3>::new();
+    /// let mut b = StaticMinHeap::<i32, 3>::new();
+    /// a.push(1).unwrap();
+    /// a.push(2).unwrap();
+    /// b.push(2).unwrap();
+    /// b.push(3).unwrap();
+    ///
+    /// let union = a.union(&b).unwrap();
+    /// assert_eq!(union.into_sorted_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    ///
+    /// b.push(4).unwrap();
+    /// assert_eq!(a.union(&b).unwrap_err(), Full(4))
+    /// ```
+    pub fn union(&self, other: &Self) -> Result<Self, Full<T>> {
+        let mut union = StaticMinHeap::new();
+        let mut overflow = None;
+        self.merge_sides(other, |_, value| {
+            if overflow.is_none() {
+                overflow = union.push(value).err();
+            }
+        });
+        match overflow {
+            None => Ok(union),
+            Some(full) => Err(full),
+        }
+    }
+
+    /// builds the multiset intersection of the two heaps, holding each
+    /// value as many times as whichever heap holds it less often
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::StaticMinHeap;
+    ///
+    /// let mut a = StaticMinHeap::<i32, 4>::new();
+    /// let mut b = StaticMinHeap::<i32, 4>::new();
+    /// for value in [1, 2, 2, 3].iter() {
+    ///     a.push(*value).unwrap();
+    /// }
+    /// for value in [2, 2, 2, 4].iter() {
+    ///     b.push(*value).unwrap();
+    /// }
+    ///
+    /// let both = a.intersection(&b);
+    /// assert_eq!(both.into_sorted_iter().collect::<Vec<_>>(), vec![2, 2])
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.diff(other).common
+    }
+
+    /// builds the multiset difference of the two heaps: this heap's values
+    /// with one copy taken away for each copy in `other`
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::StaticMinHeap;
+    ///
+    /// let mut a = StaticMinHeap::<i32, 4>::new();
+    /// let mut b = StaticMinHeap::<i32, 4>::new();
+    /// for value in [1, 2, 2, 3].iter() {
+    ///     a.push(*value).unwrap();
+    /// }
+    /// b.push(2).unwrap();
+    ///
+    /// let rest = a.difference(&b);
+    /// assert_eq!(rest.into_sorted_iter().collect::<Vec<_>>(), vec![1, 2, 3])
+    /// ```
+    pub fn difference(&self, other: &Self) -> Self {
+        self.diff(other).only_self
+    }
+
+    /// returns the value at percentile `p`, from 0 to 100, by nearest rank,
+    /// or `None` if the heap is empty
+    ///
+    /// The heap itself is left alone: the rank is selected from a copy in
+    /// O(n log n) time without allocating. The result is always one of the
+    /// heap's values, with no interpolation between neighbours, which is
+    /// where the approximation lies.
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 100.
+    /// # Example
+    /// ```
+    /// let mut latencies = min_heap::fixed::StaticMinHeap::<u32, 8>::new();
+    /// for ms in [12, 7, 30, 9, 15, 8, 11, 95].iter() {
+    ///     latencies.push(*ms).unwrap();
+    /// }
+    ///
+    /// assert_eq!(latencies.approx_percentile(0.0), Some(7));
+    /// assert_eq!(latencies.approx_percentile(50.0), Some(12));
+    /// assert_eq!(latencies.approx_percentile(100.0), Some(95));
+    /// assert_eq!(latencies.len(), 8)
+    /// ```
+    pub fn approx_percentile(&self, p: f64) -> Option<T> {
+        assert!((0.0..=100.0).contains(&p), "percentile {} is not between 0 and 100", p);
+        if self.is_empty() {
+            return None;
+        }
+
+        let rank = (p / 100.0 * (self.len - 1) as f64 + 0.5) as usize;
+        let mut copy = self.clone();
+        Some(crate::slice::select_nth(copy.as_mut_slice(), rank).clone())
+    }
+
+    // walks both heaps in ascending order at once, handing each value to
+    // `f` with the side it came from; a value in both heaps is handed over
+    // once per matching pair
+    fn merge_sides<F: FnMut(Side, T)>(&self, other: &Self, mut f: F) {
+        let mut ours = self.sorted().peekable();
+        let mut theirs = other.sorted().peekable();
+        loop {
+            let side = match (ours.peek(), theirs.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Side::OnlySelf,
+                (None, Some(_)) => Side::OnlyOther,
+                (Some(a), Some(b)) => match a.cmp(b) {
+                    Ordering::Less => Side::OnlySelf,
+                    Ordering::Greater => Side::OnlyOther,
+                    Ordering::Equal => Side::Both,
+                },
+            };
+            let value = match side {
+                Side::OnlySelf => ours.next(),
+                Side::OnlyOther => theirs.next(),
+                Side::Both => {
+                    theirs.next();
+                    ours.next()
+                }
+            };
+            f(side, value.expect("the side was picked by peeking"));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord, const N: usize> StaticMinHeap<T, N> {
+    /// counts the heap's values into buckets split at `bounds`, which must
+    /// be in ascending order
+    ///
+    /// There is one more count than there are bounds: the first counts the
+    /// values below `bounds[0]`, the one at `i` counts values from
+    /// `bounds[i - 1]` up to but not including `bounds[i]`, and the last
+    /// counts the values from the last bound up. The heap is read in place,
+    /// without popping anything.
+    /// # Example
+    /// ```
+    /// let mut latencies = min_heap::fixed::StaticMinHeap::<u32, 8>::new();
+    /// for ms in [12, 7, 30, 9, 15, 8, 11, 95].iter() {
+    ///     latencies.push(*ms).unwrap();
+    /// }
+    ///
+    /// assert_eq!(latencies.histogram(&[10, 20, 50]), vec![3, 3, 1, 1])
+    /// ```
+    pub fn histogram(&self, bounds: &[T]) -> Vec<usize> {
+        debug_assert!(bounds.windows(2).all(|w| w[0] <= w[1]), "histogram bounds are not sorted");
+
+        let mut counts = vec![0; bounds.len() + 1];
+        for value in self.as_slice() {
+            counts[bounds.partition_point(|bound| bound <= value)] += 1;
+        }
+        counts
+    }
+
+    /// merges `heaps` into one ascending iterator that only pops a value
+    /// from its sub-heap when it is reached
+    ///
+    /// The sub-heaps are kept in a heap of their own, keyed by each one's
+    /// smallest value, so taking the first `k` values costs about
+    /// `k * (log n + log m)` comparisons for `m` sub-heaps of `n` values,
+    /// however many values there are in all. Empty sub-heaps are dropped
+    /// up front, and each sub-heap is dropped as soon as it runs out.
+    /// # Example
+    /// ```
+    /// use min_heap::fixed::StaticMinHeap;
+    ///
+    /// let mut shards = vec![];
+    /// for values in [[9, 1, 5], [4, 8, 2], [7, 3, 6]].iter() {
+    ///     let mut shard = StaticMinHeap::<u32, 4>::new();
+    ///     for v in values.iter() {
+    ///         shard.push(*v).unwrap();
+    ///     }
+    ///     shards.push(shard);
+    /// }
+    ///
+    /// let smallest: Vec<u32> = StaticMinHeap::merge_many(shards).take(4).collect();
+    /// assert_eq!(smallest, vec![1, 2, 3, 4])
+    /// ```
+    pub fn merge_many(heaps: Vec<Self>) -> MergeMany<T, N> {
+        let mut heaps: Vec<Self> = heaps.into_iter().filter(|heap| !heap.is_empty()).collect();
+        crate::sift::heapify(&mut heaps, &lower_min);
+        let remaining = heaps.iter().map(Self::len).sum();
+        MergeMany { heaps, remaining }
+    }
+}
+
+// orders sub-heaps by their smallest value; only non-empty ones are compared
+#[cfg(feature = "std")]
+fn lower_min<T: Ord, const N: usize>(a: &StaticMinHeap<T, N>, b: &StaticMinHeap<T, N>) -> bool {
+    a.peek() < b.peek()
+}
+
+/// The result of `StaticMinHeap::diff`, with each value of the two heaps
+/// in exactly one part.
+#[derive(Debug, Clone)]
+pub struct Diff<T, const N: usize> {
+    /// values only in the heap `diff` was called on
+    pub only_self: StaticMinHeap<T, N>,
+    /// values only in the heap passed to `diff`
+    pub only_other: StaticMinHeap<T, N>,
+    /// values in both heaps
+    pub common: StaticMinHeap<T, N>,
+}
+
+// where `merge_sides` found a value
+enum Side {
+    OnlySelf,
+    OnlyOther,
+    Both,
+}
+
+/// The iterator returned by `StaticMinHeap::into_sorted_iter`, which pops
+/// the heap's values in ascending order as it goes.
+pub struct IntoSortedIter<T, const N: usize>(StaticMinHeap<T, N>);
+
+impl<T: Ord, const N: usize> Iterator for IntoSortedIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T: Ord, const N: usize> ExactSizeIterator for IntoSortedIter<T, N> {}
+
+/// The iterator returned by `StaticMinHeap::merge_many`, which yields the
+/// values of all its sub-heaps in ascending order.
+#[cfg(feature = "std")]
+pub struct MergeMany<T, const N: usize> {
+    heaps: Vec<StaticMinHeap<T, N>>,
+    remaining: usize,
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord, const N: usize> Iterator for MergeMany<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = self.heaps.first_mut()?.pop();
+        if self.heaps[0].is_empty() {
+            self.heaps.swap_remove(0);
+        }
+        sift_down(&mut self.heaps, 0, &lower_min);
+        self.remaining -= 1;
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord, const N: usize> ExactSizeIterator for MergeMany<T, N> {}
+
+/// The iterator returned by `StaticMinHeap::pop_batch`. Each value is
+/// only removed from the heap when the iterator reaches it.
+#[must_use = "the batch is only popped as the iterator is consumed"]
+pub struct PopBatch<'a, T, P, const N: usize> {
+    heap: &'a mut StaticMinHeap<T, N>,
+    remaining: usize,
+    pred: P,
+}
+
+impl<T: Ord, P: FnMut(&T) -> bool, const N: usize> Iterator for PopBatch<'_, T, P, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        match self.heap.peek() {
+            Some(min) if (self.pred)(min) => {
+                self.remaining -= 1;
+                self.heap.pop()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Two heaps are equal when they hold the same values, however those
+/// values happen to be arranged in the backing array.
+impl<T: Ord + Clone, const N: usize> PartialEq for StaticMinHeap<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.sorted().eq(other.sorted())
+    }
+}
+
+impl<T: Ord + Clone, const N: usize> Eq for StaticMinHeap<T, N> {}
+
+/// Heaps are ordered by comparing their sorted values lexicographically,
+/// so `[1, 5]` comes before `[2]` and `[1]` comes before `[1, 1]`.
+impl<T: Ord + Clone, const N: usize> PartialOrd for StaticMinHeap<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord + Clone, const N: usize> Ord for StaticMinHeap<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sorted().cmp(other.sorted())
+    }
+}
+
+/// Hashes the values in sorted order, so heaps that are equal hash the
+/// same even when their arrays are laid out differently.
+impl<T: Ord + Clone + Hash, const N: usize> Hash for StaticMinHeap<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for value in self.sorted() {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for StaticMinHeap<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+fn less<T: Ord>(a: &T, b: &T) -> bool {
+    a < b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slice::is_heap;
+    use std::rc::Rc;
+
+    #[test]
+    fn pops_in_ascending_order() {
+        let mut heap = StaticMinHeap::<i32, 8>::new();
+        for value in [6, 2, 7, 1, 8, 3, 5, 4].iter() {
+            heap.push(*value).unwrap();
+            assert!(is_heap(heap.as_slice()));
+        }
+
+        let mut popped = vec![];
+        while let Some(value) = heap.pop() {
+            popped.push(value);
+        }
+        assert_eq!(popped, vec![1, 2, 3, 4, 5, 6, 7, 8])
+    }
+
+    #[test]
+    fn pop_batch_stops_at_limit_and_predicate() {
+        let mut heap = StaticMinHeap::<i32, 8>::new();
+        for value in [5, 3, 8, 1, 6].iter() {
+            heap.push(*value).unwrap();
+        }
+
+        assert_eq!(heap.pop_batch(0, |_| true).next(), None);
+        assert_eq!(heap.pop_batch(2, |_| true).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(heap.pop_batch(10, |v| v % 2 == 1).collect::<Vec<_>>(), vec![5]);
+        assert_eq!(heap.len(), 2);
+        assert!(is_heap(heap.as_slice()));
+
+        // a batch that is dropped early leaves the rest in the heap
+        assert_eq!(heap.pop_batch(10, |_| true).next(), Some(6));
+        assert_eq!(heap.len(), 1);
+        assert_eq!(heap.pop_batch(10, |_| true).collect::<Vec<_>>(), vec![8]);
+        assert!(heap.is_empty())
+    }
+
+    // values not reached before the iterator is dropped are dropped with it
+    #[test]
+    fn into_sorted_iter_pops_lazily() {
+        let shared = Rc::new(0);
+        let mut heap = StaticMinHeap::<(i32, Rc<i32>), 4>::new();
+        for value in [4, 2, 3].iter() {
+            heap.push((*value, Rc::clone(&shared))).unwrap();
+        }
+
+        let mut sorted = heap.into_sorted_iter();
+        assert_eq!(sorted.size_hint(), (3, Some(3)));
+        assert_eq!(sorted.next().map(|(v, _)| v), Some(2));
+        assert_eq!(Rc::strong_count(&shared), 3);
+
+        drop(sorted);
+        assert_eq!(Rc::strong_count(&shared), 1)
+    }
+
+    #[test]
+    fn pop_if_le_respects_bound() {
+        let mut heap = StaticMinHeap::<i32, 4>::new();
+        assert_eq!(heap.pop_if_le(&i32::MAX), None);
+
+        heap.push(3).unwrap();
+        heap.push(5).unwrap();
+        assert_eq!(heap.pop_if_le(&2), None);
+        assert_eq!(heap.pop_if_le(&3), Some(3));
+        assert_eq!(heap.pop_if_le(&4), None);
+        assert_eq!(heap.peek(), Some(&5))
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn full_is_an_error() {
+        fn push_two() -> Result<(), Box<dyn std::error::Error>> {
+            let mut heap = StaticMinHeap::<i32, 1>::new();
+            heap.push(1)?;
+            heap.push(2)?;
+            Ok(())
+        }
+
+        assert_eq!(push_two().unwrap_err().to_string(), "heap is full")
+    }
+
+    fn heap_of(values: &[i32]) -> StaticMinHeap<i32, 8> {
+        let mut heap = StaticMinHeap::new();
+        for value in values {
+            heap.push(*value).unwrap();
+        }
+        heap
+    }
+
+    fn drained(heap: StaticMinHeap<i32, 8>) -> Vec<i32> {
+        heap.into_sorted_iter().collect()
+    }
+
+    #[test]
+    fn diff_splits_multisets() {
+        let diff = heap_of(&[4, 1, 3, 3, 3, 9]).diff(&heap_of(&[3, 8, 1, 3, 0]));
+        assert_eq!(drained(diff.only_self), vec![3, 4, 9]);
+        assert_eq!(drained(diff.only_other), vec![0, 8]);
+        assert_eq!(drained(diff.common), vec![1, 3, 3]);
+
+        let diff = heap_of(&[2, 1]).diff(&heap_of(&[]));
+        assert_eq!(drained(diff.only_self), vec![1, 2]);
+        assert!(diff.only_other.is_empty() && diff.common.is_empty())
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = heap_of(&[5, 1, 3, 3, 7]);
+        let b = heap_of(&[3, 8, 1, 1]);
+
+        assert_eq!(drained(a.union(&b).unwrap()), vec![1, 1, 3, 3, 5, 7, 8]);
+        assert_eq!(drained(a.intersection(&b)), vec![1, 3]);
+        assert_eq!(drained(a.difference(&b)), vec![3, 5, 7]);
+        assert_eq!(drained(b.difference(&a)), vec![1, 8]);
+
+        // the result must be a valid heap to keep using
+        let mut union = a.union(&b).unwrap();
+        union.push(2).unwrap();
+        assert!(is_heap(union.as_slice()))
+    }
+
+    #[test]
+    fn union_overflow_returns_first_value_left_out() {
+        let a = heap_of(&[1, 2, 3, 4, 5]);
+        let b = heap_of(&[6, 7, 8, 9]);
+        assert_eq!(a.union(&b).unwrap_err(), Full(9));
+
+        let same = heap_of(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(drained(same.union(&same).unwrap()), vec![1, 2, 3, 4, 5, 6, 7, 8])
+    }
+
+    #[test]
+    fn percentiles_by_nearest_rank() {
+        let heap = heap_of(&[40, 10, 30, 20, 50]);
+        assert_eq!(heap.approx_percentile(0.0), Some(10));
+        assert_eq!(heap.approx_percentile(25.0), Some(20));
+        assert_eq!(heap.approx_percentile(60.0), Some(30));
+        assert_eq!(heap.approx_percentile(90.0), Some(50));
+        assert_eq!(heap.approx_percentile(100.0), Some(50));
+
+        // the heap is untouched
+        assert_eq!(drained(heap), vec![10, 20, 30, 40, 50]);
+        assert_eq!(heap_of(&[]).approx_percentile(50.0), None)
+    }
+
+    #[test]
+    #[should_panic(expected = "not between 0 and 100")]
+    fn percentile_out_of_range() {
+        heap_of(&[1]).approx_percentile(f64::NAN);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn histogram_buckets() {
+        let heap = heap_of(&[1, 5, 5, 9, 10, 12, 0]);
+        assert_eq!(heap.histogram(&[5, 10]), vec![2, 3, 2]);
+        assert_eq!(heap.histogram(&[]), vec![7]);
+        assert_eq!(heap_of(&[]).histogram(&[3]), vec![0, 0])
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn merge_many_yields_everything_in_order() {
+        let shards = vec![heap_of(&[4, 1, 7]), heap_of(&[]), heap_of(&[3, 3, 9, 0]), heap_of(&[8])];
+        let mut merged = StaticMinHeap::merge_many(shards);
+        assert_eq!(merged.len(), 8);
+        assert_eq!(merged.next(), Some(0));
+        assert_eq!(merged.len(), 7);
+        assert_eq!(merged.collect::<Vec<_>>(), vec![1, 3, 3, 4, 7, 8, 9]);
+
+        assert_eq!(StaticMinHeap::<i32, 8>::merge_many(vec![]).next(), None)
+    }
+
+    // taking a few values must leave the rest of each sub-heap unpopped
+    #[cfg(feature = "std")]
+    #[test]
+    fn merge_many_pops_lazily() {
+        let shards: Vec<_> = (0..4).map(|s| heap_of(&[s, s + 10, s + 20])).collect();
+        let mut merged = StaticMinHeap::merge_many(shards);
+        assert_eq!(merged.by_ref().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let left: usize = merged.heaps.iter().map(StaticMinHeap::len).sum();
+        assert_eq!(left, 9);
+        assert_eq!(merged.heaps.len(), 4)
+    }
+
+    #[test]
+    fn full_heap_returns_value() {
+        let mut heap = StaticMinHeap::<i32, 2>::new();
+        heap.push(3).unwrap();
+        heap.push(1).unwrap();
+
+        assert!(heap.is_full());
+        assert_eq!(heap.push(0), Err(Full(0)));
+        assert_eq!(heap.len(), 2);
+        assert_eq!(heap.peek(), Some(&1))
+    }
+
+    #[test]
+    fn const_constructed() {
+        static HEAP: StaticMinHeap<i32, 4> = StaticMinHeap::new();
+
+        assert_eq!(HEAP.len(), 0);
+        assert_eq!(HEAP.capacity(), 4);
+        assert_eq!(HEAP.peek(), None)
+    }
+
+    #[test]
+    fn unchecked_accessors() {
+        let mut heap = StaticMinHeap::<i32, 4>::new();
+        for value in [4, 2, 3].iter() {
+            heap.push(*value).unwrap();
+        }
+
+        unsafe {
+            assert_eq!(*heap.peek_unchecked(), 2);
+            assert_eq!(*heap.get_unchecked(0), 2);
+            assert_eq!(heap.pop_unchecked(), 2);
+            assert_eq!(heap.pop_unchecked(), 3);
+        }
+        assert_eq!(heap.pop(), Some(4))
+    }
+
+    #[test]
+    fn clone_keeps_layout() {
+        let mut heap = StaticMinHeap::<i32, 8>::new();
+        for value in [5, 3, 8, 1].iter() {
+            heap.push(*value).unwrap();
+        }
+
+        let copy = heap.clone();
+        assert_eq!(copy.as_slice(), heap.as_slice());
+        assert_eq!(copy.sorted().collect::<Vec<i32>>(), vec![1, 3, 5, 8])
+    }
+
+    // heaps with the same values in different layouts are equal and hash
+    // the same, so they can be used as map keys
+    #[test]
+    fn equality_and_hash_ignore_layout() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+
+        let mut a = StaticMinHeap::<i32, 8>::new();
+        let mut b = StaticMinHeap::<i32, 8>::new();
+        for value in [1, 2, 3, 4, 2].iter() {
+            a.push(*value).unwrap();
+        }
+        for value in [4, 2, 2, 3, 1].iter() {
+            b.push(*value).unwrap();
+        }
+        assert_ne!(a.as_slice(), b.as_slice());
+        assert_eq!(a, b);
+
+        let hash = |heap: &StaticMinHeap<i32, 8>| {
+            let mut hasher = DefaultHasher::new();
+            heap.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&a), hash(&b));
+
+        let mut seen = HashSet::new();
+        seen.insert(a);
+        assert!(seen.contains(&b));
+
+        b.pop();
+        assert!(!seen.contains(&b))
+    }
+
+    #[test]
+    fn ordered_by_sorted_contents() {
+        use std::collections::BTreeSet;
+
+        let heap = |values: &[i32]| {
+            let mut heap = StaticMinHeap::<i32, 4>::new();
+            for value in values {
+                heap.push(*value).unwrap();
+            }
+            heap
+        };
+
+        assert!(heap(&[5, 1]) < heap(&[2]));
+        assert!(heap(&[1]) < heap(&[1, 1]));
+        assert!(heap(&[]) < heap(&[0]));
+        assert_eq!(heap(&[3, 1, 2]).cmp(&heap(&[2, 3, 1])), Ordering::Equal);
+
+        let set: BTreeSet<_> = vec![heap(&[3]), heap(&[1, 9]), heap(&[9, 1]), heap(&[2, 2])]
+            .into_iter()
+            .collect();
+        let firsts: Vec<Option<i32>> = set.iter().map(|h| h.peek().cloned()).collect();
+        assert_eq!(firsts, vec![Some(1), Some(2), Some(3)])
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut heap = StaticMinHeap::<i32, 0>::new();
+        assert_eq!(heap.push(1), Err(Full(1)));
+        assert_eq!(heap.pop(), None)
+    }
+
+    // values still in the heap are dropped with it, popped ones are not
+    #[test]
+    fn drops_remaining_values() {
+        let value = Rc::new(());
+        let mut heap = StaticMinHeap::<(i32, Rc<()>), 4>::new();
+        for i in 0..3 {
+            heap.push((i, Rc::clone(&value))).unwrap();
+        }
+
+        let popped = heap.pop();
+        assert_eq!(Rc::strong_count(&value), 4);
+        drop(heap);
+        assert_eq!(Rc::strong_count(&value), 2);
+        drop(popped);
+        assert_eq!(Rc::strong_count(&value), 1)
+    }
+}
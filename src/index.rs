@@ -0,0 +1,120 @@
+use core::ops::Range;
+
+/// returns the index of the parent of index `i` in a binary heap stored as
+/// an implicit array, or `None` for the root at index 0
+/// # Example
+/// ```
+/// use min_heap::index::parent_of;
+///
+/// assert_eq!(parent_of(0), None);
+/// assert_eq!(parent_of(1), Some(0));
+/// assert_eq!(parent_of(6), Some(2))
+/// ```
+pub fn parent_of(i: usize) -> Option<usize> {
+    d_ary_parent_of(i, 2)
+}
+
+/// returns the index of the left child of index `i` in a binary heap
+/// # Example
+/// ```
+/// assert_eq!(min_heap::index::left_child_of(2), 5)
+/// ```
+pub fn left_child_of(i: usize) -> usize {
+    d_ary_child_of(i, 2, 0)
+}
+
+/// returns the index of the right child of index `i` in a binary heap
+/// # Example
+/// ```
+/// assert_eq!(min_heap::index::right_child_of(2), 6)
+/// ```
+pub fn right_child_of(i: usize) -> usize {
+    d_ary_child_of(i, 2, 1)
+}
+
+/// returns the index of the parent of index `i` in a heap where every
+/// node has `d` children, or `None` for the root at index 0
+///
+/// # Panics
+/// Panics if `d` is 0.
+/// # Example
+/// ```
+/// use min_heap::index::d_ary_parent_of;
+///
+/// assert_eq!(d_ary_parent_of(0, 4), None);
+/// assert_eq!(d_ary_parent_of(4, 4), Some(0));
+/// assert_eq!(d_ary_parent_of(5, 4), Some(1))
+/// ```
+pub fn d_ary_parent_of(i: usize, d: usize) -> Option<usize> {
+    assert!(d > 0, "a heap needs at least one child per node");
+    if i == 0 {
+        None
+    } else {
+        Some((i - 1) / d)
+    }
+}
+
+/// returns the index of child `k` (counting from 0) of index `i` in a heap
+/// where every node has `d` children
+/// # Example
+/// ```
+/// assert_eq!(min_heap::index::d_ary_child_of(1, 3, 2), 6)
+/// ```
+pub fn d_ary_child_of(i: usize, d: usize, k: usize) -> usize {
+    debug_assert!(k < d, "child {} of a {}-ary heap node", k, d);
+    d * i + 1 + k
+}
+
+/// returns the range of indexes of the children of index `i` in a heap
+/// where every node has `d` children
+///
+/// The range can extend past the end of the heap; only the part below its
+/// length holds values.
+/// # Example
+/// ```
+/// assert_eq!(min_heap::index::d_ary_children_of(1, 3), 4..7)
+/// ```
+pub fn d_ary_children_of(i: usize, d: usize) -> Range<usize> {
+    let first = d * i + 1;
+    first..first + d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every child's parent is the node it came from
+    #[test]
+    fn parents_and_children_agree() {
+        for i in 0..100 {
+            assert_eq!(parent_of(left_child_of(i)), Some(i));
+            assert_eq!(parent_of(right_child_of(i)), Some(i));
+
+            for d in 1..6 {
+                for child in d_ary_children_of(i, d) {
+                    assert_eq!(d_ary_parent_of(child, d), Some(i));
+                }
+                assert_eq!(d_ary_child_of(i, d, 0), d_ary_children_of(i, d).start);
+            }
+        }
+    }
+
+    // the children of consecutive nodes tile the array with no gaps
+    #[test]
+    fn children_cover_every_index() {
+        for d in 1..6 {
+            let mut next = 1;
+            for i in 0..50 {
+                let children = d_ary_children_of(i, d);
+                assert_eq!(children.start, next);
+                next = children.end;
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_arity() {
+        d_ary_parent_of(3, 0);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::sift::{sift_down, sift_up};
+
+/// Extension methods for picking the smallest or largest items out of
+/// any iterator.
+///
+/// Both methods keep a bounded heap of `k` items while consuming the
+/// iterator, so they run in O(n log k) time with O(k) extra memory.
+pub trait HeapIterExt: Iterator {
+    /// returns the `k` smallest items, in ascending order
+    /// # Example
+    /// ```
+    /// use min_heap::iter::HeapIterExt;
+    ///
+    /// let smallest: Vec<i32> = vec![7, 2, 9, 4, 1, 8].into_iter().k_smallest(3).collect();
+    /// assert_eq!(smallest, vec![1, 2, 4])
+    /// ```
+    fn k_smallest(self, k: usize) -> std::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        // the largest kept item sits at the root, ready to be replaced
+        let mut kept = bounded(self, k, &|a: &Self::Item, b: &Self::Item| a > b);
+        kept.sort();
+        kept.into_iter()
+    }
+
+    /// returns the `k` largest items, in descending order
+    /// # Example
+    /// ```
+    /// use min_heap::iter::HeapIterExt;
+    ///
+    /// let largest: Vec<i32> = vec![7, 2, 9, 4, 1, 8].into_iter().k_largest(2).collect();
+    /// assert_eq!(largest, vec![9, 8])
+    /// ```
+    fn k_largest(self, k: usize) -> std::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        // the smallest kept item sits at the root, ready to be replaced
+        let mut kept = bounded(self, k, &|a: &Self::Item, b: &Self::Item| a < b);
+        kept.sort_by(|a, b| b.cmp(a));
+        kept.into_iter()
+    }
+}
+
+impl<I: Iterator> HeapIterExt for I {}
+
+// keeps the k items that `above` would push furthest down the heap,
+// evicting the root whenever a better item comes along
+fn bounded<I, F>(iter: I, k: usize, above: &F) -> Vec<I::Item>
+where
+    I: Iterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    // `k` may be far larger than the input, so only reserve what the
+    // iterator promises to yield
+    let mut kept = Vec::with_capacity(k.min(iter.size_hint().0));
+    if k == 0 {
+        return kept;
+    }
+
+    for item in iter {
+        if kept.len() < k {
+            kept.push(item);
+            let last = kept.len() - 1;
+            sift_up(&mut kept, last, above);
+        } else if above(&kept[0], &item) {
+            kept[0] = item;
+            sift_down(&mut kept, 0, above);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn k_smallest_in_order() {
+        let values = vec![5, 3, 8, 1, 9, 2, 7];
+        let smallest: Vec<i32> = values.into_iter().k_smallest(4).collect();
+        assert_eq!(smallest, vec![1, 2, 3, 5])
+    }
+
+    #[test]
+    fn k_largest_in_order() {
+        let values = vec![5, 3, 8, 1, 9, 2, 7];
+        let largest: Vec<i32> = values.into_iter().k_largest(3).collect();
+        assert_eq!(largest, vec![9, 8, 7])
+    }
+
+    #[test]
+    fn k_smallest_keeps_duplicates() {
+        let values = vec![4, 1, 4, 1, 4];
+        let smallest: Vec<i32> = values.into_iter().k_smallest(3).collect();
+        assert_eq!(smallest, vec![1, 1, 4])
+    }
+
+    // asking for more than there is, or for nothing at all
+    #[test]
+    fn k_out_of_range() {
+        let all: Vec<i32> = vec![3, 1, 2].into_iter().k_smallest(10).collect();
+        assert_eq!(all, vec![1, 2, 3]);
+
+        let none: Vec<i32> = vec![3, 1, 2].into_iter().k_largest(0).collect();
+        assert!(none.is_empty());
+
+        // a huge k must not be allocated up front
+        let all: Vec<i32> = vec![3, 1, 2].into_iter().k_smallest(usize::MAX).collect();
+        assert_eq!(all, vec![1, 2, 3]);
+        let all: Vec<i32> = vec![3, 1, 2].into_iter().k_largest(1 << 40).collect();
+        assert_eq!(all, vec![3, 2, 1])
+    }
+}
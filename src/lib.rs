@@ -1,8 +1,29 @@
+// the array heaps and slice helpers only need `core`; unit tests always
+// get std so they can use `Vec` and friends
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
 //pub use node;
 
+pub mod binary_heap;
+#[cfg(feature = "std")]
+pub mod debug;
+pub mod expiry;
+pub mod fixed;
+pub mod index;
+#[cfg(feature = "std")]
+pub mod iter;
+#[cfg(feature = "std")]
 pub mod node;
+pub mod priority;
+#[cfg(feature = "std")]
+pub mod record;
+pub mod slice;
+
+mod sift;
+#[cfg(feature = "sync-nodes")]
+mod sync;
 /*
 struct Tree<'a> {
     node: ,
 }
-*/
\ No newline at end of file
+*/
@@ -1,6 +1,108 @@
-use std::fmt;
+#[cfg(not(feature = "sync-nodes"))]
 use std::rc::{Rc, Weak};
-use std::cell::RefCell;
+#[cfg(not(feature = "sync-nodes"))]
+use std::cell::{Cell, RefCell};
+#[cfg(not(feature = "sync-nodes"))]
+type ValueCell = Cell<i32>;
+
+// with `sync-nodes` these names are Arc, sync::Weak, a mutex and a
+// RwLock wrapper
+#[cfg(feature = "sync-nodes")]
+use crate::sync::{Rc, RefCell, ValueCell, Weak};
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::index::parent_of;
+use crate::slice::HeapSlice;
+
+/// The most children a node in the binary heap can have.
+pub const MAX_CHILDREN: usize = 2;
+
+/// The ways linking two nodes can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkError {
+    /// the parent already has `MAX_CHILDREN` children
+    ParentFull,
+    /// the child is already linked to this parent
+    Duplicate,
+    /// the child is already linked to a different parent
+    AlreadyHasParent,
+    /// the child is the parent itself or one of its ancestors, so linking
+    /// them would make a reference cycle that is never freed
+    Cycle,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::ParentFull => write!(f, "parent already has {} children", MAX_CHILDREN),
+            LinkError::Duplicate => write!(f, "child is already linked to this parent"),
+            LinkError::AlreadyHasParent => write!(f, "child is already linked to another parent"),
+            LinkError::Cycle => write!(f, "child is an ancestor of the parent"),
+        }
+    }
+}
+
+impl Error for LinkError {}
+
+/// A change to the links between nodes, reported to the hook set with
+/// `set_link_hook`. Nodes are identified by their values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkEvent {
+    /// a child was attached to a parent
+    Linked { parent: i32, child: i32 },
+    /// a child was detached from its parent
+    Unlinked { parent: i32, child: i32 },
+}
+
+// shared rather than boxed, so `emit` can call the hook without keeping
+// the slot borrowed
+type LinkHook = std::rc::Rc<dyn Fn(&LinkEvent)>;
+
+thread_local! {
+    static LINK_HOOK: std::cell::RefCell<Option<LinkHook>> = std::cell::RefCell::new(None);
+}
+
+/// sets a hook that is called with every `LinkEvent` on the current
+/// thread, replacing any earlier hook
+///
+/// Nothing is reported until a hook is set. The hook runs after the
+/// links have been changed, so it can't see a tree halfway through an
+/// update. The hook may set or clear the hook itself; the change applies
+/// from the next event on.
+/// # Example
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use min_heap::node::{self, LinkEvent, Node};
+///
+/// let events = Rc::new(RefCell::new(vec![]));
+/// let seen = Rc::clone(&events);
+/// node::set_link_hook(move |event| seen.borrow_mut().push(*event));
+///
+/// let parent = Node::new_orphan(1);
+/// Node::new_child(&parent, 2).unwrap();
+/// node::clear_link_hook();
+///
+/// assert_eq!(*events.borrow(), vec![LinkEvent::Linked { parent: 1, child: 2 }]);
+/// ```
+pub fn set_link_hook<F: Fn(&LinkEvent) + 'static>(hook: F) {
+    LINK_HOOK.with(|h| *h.borrow_mut() = Some(std::rc::Rc::new(hook)));
+}
+
+/// removes the current thread's link hook, if there is one
+pub fn clear_link_hook() {
+    LINK_HOOK.with(|h| *h.borrow_mut() = None);
+}
+
+fn emit(event: LinkEvent) {
+    let hook = LINK_HOOK.with(|h| h.borrow().clone());
+    if let Some(hook) = hook {
+        hook(&event);
+    }
+}
 
 
 /// A node type in the binary minimum heap. This is a single 'piece' of a heap.
@@ -10,9 +112,13 @@ use std::cell::RefCell;
 /// The node owns the children, and has a reference to the parent.
 /// 
 /// If the parent is deallocated, so are its children.
+///
+/// With the `sync-nodes` feature the links are `Arc`s and `RwLock`s
+/// instead of `Rc`s and `RefCell`s, so whole subtrees can be sent to
+/// other threads. Everywhere this module says `Rc` read `Arc`.
 #[derive(Debug)]
 pub struct Node {
-    value: i32,
+    value: ValueCell,
     parent: RefCell<Weak<Node>>,
     children: RefCell<Vec<Rc<Node>>>,
 }
@@ -26,7 +132,7 @@ impl Node {
     /// ```
     pub fn new_orphan(value: i32) -> Rc<Node> {
         Rc::new(Node{
-            value,
+            value: ValueCell::new(value),
             parent: RefCell::new(Weak::new()),
             children: RefCell::new(vec![]),
         })
@@ -36,30 +142,56 @@ impl Node {
     /// the passed in value.
     /// 
     /// Parent and Child relationship setup automatically
+    ///
+    /// Returns `LinkError::ParentFull` if the parent already has
+    /// `MAX_CHILDREN` children.
     /// # Example
     /// ```
     /// let parent = min_heap::node::Node::new_orphan(5);
     /// let val = vec![24];
-    /// min_heap::node::Node::new_child(&parent, 24);
+    /// min_heap::node::Node::new_child(&parent, 24).unwrap();
     /// assert_eq!(parent.get_child_values(), val)
     /// ```
-    pub fn new_child(parent: &Rc<Node>, child_value: i32) {
+    pub fn new_child(parent: &Rc<Node>, child_value: i32) -> Result<(), LinkError> {
+        if parent.children.borrow().len() >= MAX_CHILDREN {
+            return Err(LinkError::ParentFull);
+        }
+
         // creating a new node with the parent being the passed in node
         let child = Rc::new(Node{
-            value : child_value,
-            parent: RefCell::new(Rc::downgrade(&parent)),
+            value : ValueCell::new(child_value),
+            parent: RefCell::new(Rc::downgrade(parent)),
             children: RefCell::new(vec![]),
         });
 
         // pushing a strong reference the of the new node
         // into the child vector of the parent node
         parent.children.borrow_mut().push(Rc::clone(&child));
+        emit(LinkEvent::Linked { parent: parent.get_value(), child: child_value });
+        Ok(())
     }
 
     /// swaps a parent with a child.
     /// this is done by simply swapping the values
+    ///
+    /// The values live in a `Cell`, so no borrow of either node is taken
+    /// and a swap can't clash with a walk that is borrowing the children.
+    ///
+    /// With `sync-nodes` both values are locked for the whole swap, so
+    /// swaps on different threads that share a node never lose or
+    /// duplicate a value.
+    /// # Example
+    /// ```
+    /// let parent = min_heap::node::Node::new_orphan(8);
+    /// let child = min_heap::node::Node::new_orphan(3);
+    /// min_heap::node::Node::parent_child(&parent, &child).unwrap();
+    ///
+    /// min_heap::node::Node::swap(&parent, &child);
+    /// assert_eq!(parent.get_value(), 3);
+    /// assert_eq!(child.get_value(), 8)
+    /// ```
     pub fn swap(parent: &Rc<Node>, child: &Rc<Node>) {
-        unimplemented!();
+        parent.value.swap(&child.value);
     }
 
     /// returns the value field of the Node struct
@@ -72,7 +204,7 @@ impl Node {
     /// assert_eq!(num, node1.get_value())
     /// ```
     pub fn get_value(&self) -> i32 {
-        self.value
+        self.value.get()
     }
 
     /// returns an array of the node's child values
@@ -82,8 +214,8 @@ impl Node {
     /// let node2 = min_heap::node::Node::new_orphan(2);
     /// let node3 = min_heap::node::Node::new_orphan(3);
     /// 
-    /// min_heap::node::Node::parent_child(&node1, &node2);
-    /// min_heap::node::Node::parent_child(&node1, &node3);
+    /// min_heap::node::Node::parent_child(&node1, &node2).unwrap();
+    /// min_heap::node::Node::parent_child(&node1, &node3).unwrap();
     /// 
     /// let arr : Vec<i32> = vec![2, 3];
     /// 
@@ -98,16 +230,733 @@ impl Node {
         result
     }
 
+    /// returns a weak reference to the node, which lets an observer look at
+    /// it without keeping it alive
+    /// # Example
+    /// ```
+    /// let node = min_heap::node::Node::new_orphan(4);
+    /// let weak = min_heap::node::Node::downgrade(&node);
+    /// assert_eq!(weak.upgrade().map(|n| n.get_value()), Some(4));
+    ///
+    /// drop(node);
+    /// assert!(weak.upgrade().is_none())
+    /// ```
+    pub fn downgrade(node: &Rc<Node>) -> Weak<Node> {
+        Rc::downgrade(node)
+    }
+
+    /// returns a weak reference to the node's parent, which is empty if the
+    /// node has no parent or the parent has been dropped
+    /// # Example
+    /// ```
+    /// let parent = min_heap::node::Node::new_orphan(1);
+    /// let child = min_heap::node::Node::new_orphan(2);
+    /// min_heap::node::Node::parent_child(&parent, &child).unwrap();
+    ///
+    /// let weak = child.parent_weak();
+    /// assert_eq!(weak.upgrade().map(|n| n.get_value()), Some(1))
+    /// ```
+    pub fn parent_weak(&self) -> Weak<Node> {
+        self.parent.borrow().clone()
+    }
+
+    /// returns the number of strong references to the node, which
+    /// includes the one its parent holds
+    /// # Example
+    /// ```
+    /// use min_heap::node::Node;
+    ///
+    /// let parent = Node::new_orphan(1);
+    /// let child = Node::new_orphan(2);
+    /// Node::parent_child(&parent, &child).unwrap();
+    /// assert_eq!(Node::strong_count(&child), 2)
+    /// ```
+    pub fn strong_count(node: &Rc<Node>) -> usize {
+        Rc::strong_count(node)
+    }
+
+    /// returns the number of weak references to the node, which includes
+    /// the ones each of its children holds
+    /// # Example
+    /// ```
+    /// use min_heap::node::Node;
+    ///
+    /// let parent = Node::new_orphan(1);
+    /// Node::new_child(&parent, 2).unwrap();
+    /// Node::new_child(&parent, 3).unwrap();
+    /// assert_eq!(Node::weak_count(&parent), 2)
+    /// ```
+    pub fn weak_count(node: &Rc<Node>) -> usize {
+        Rc::weak_count(node)
+    }
+
+    // weak references to every node below (and including) this one
+    pub(crate) fn downgrade_all(node: &Rc<Node>) -> Vec<Weak<Node>> {
+        let mut all = vec![Rc::downgrade(node)];
+        for child in node.children.borrow().iter() {
+            all.extend(Node::downgrade_all(child));
+        }
+        all
+    }
+
     /// An associated function of Node that takes two nodes
     /// that have a parent-child relationship and stores references to each
-    pub fn parent_child(parent: &Rc<Node>, child: &Rc<Node>) {
-        println!("child parent = {:?}", child.parent.borrow().upgrade());
+    ///
+    /// Misuse is reported without changing anything:
+    /// - `LinkError::Duplicate` if the child is already this parent's
+    /// - `LinkError::AlreadyHasParent` if the child belongs to another
+    ///   parent (use `reparent` to move it)
+    /// - `LinkError::Cycle` if the child is the parent or one of its
+    ///   ancestors
+    /// - `LinkError::ParentFull` if the parent already has
+    ///   `MAX_CHILDREN` children
+    /// # Example
+    /// ```
+    /// use min_heap::node::{LinkError, Node};
+    ///
+    /// let parent = Node::new_orphan(1);
+    /// let child = Node::new_orphan(2);
+    /// assert_eq!(Node::parent_child(&parent, &child), Ok(()));
+    /// assert_eq!(Node::parent_child(&parent, &child), Err(LinkError::Duplicate))
+    /// ```
+    pub fn parent_child(parent: &Rc<Node>, child: &Rc<Node>) -> Result<(), LinkError> {
+        if parent.children.borrow().iter().any(|c| Rc::ptr_eq(c, child)) {
+            return Err(LinkError::Duplicate);
+        }
+        if child.parent.borrow().upgrade().is_some() {
+            return Err(LinkError::AlreadyHasParent);
+        }
+        if Node::is_ancestor_or_self(child, parent) {
+            return Err(LinkError::Cycle);
+        }
+        if parent.children.borrow().len() >= MAX_CHILDREN {
+            return Err(LinkError::ParentFull);
+        }
     
         //storing a weak reference of parent in the child
-        *child.parent.borrow_mut() = Rc::downgrade(&parent);
+        *child.parent.borrow_mut() = Rc::downgrade(parent);
     
         //pushing a strong reference of the child into the parent
         parent.children.borrow_mut().push(Rc::clone(child));
+        emit(LinkEvent::Linked { parent: parent.get_value(), child: child.get_value() });
+        Ok(())
+    }
+
+    /// moves a child from its current parent (if it has one) to a new
+    /// parent, becoming the new parent's last child
+    ///
+    /// If the new parent is full the tree is left untouched and
+    /// `LinkError::ParentFull` is returned, and if the new parent is the
+    /// child itself or one of its descendants `LinkError::Cycle` is
+    /// returned. Moving a child to the parent it already has does nothing.
+    /// # Example
+    /// ```
+    /// use min_heap::node::Node;
+    ///
+    /// let old_parent = Node::new_orphan(1);
+    /// let new_parent = Node::new_orphan(2);
+    /// let child = Node::new_orphan(3);
+    /// Node::parent_child(&old_parent, &child).unwrap();
+    ///
+    /// Node::reparent(&child, &new_parent).unwrap();
+    /// assert!(old_parent.get_child_values().is_empty());
+    /// assert_eq!(new_parent.get_child_values(), vec![3])
+    /// ```
+    pub fn reparent(child: &Rc<Node>, new_parent: &Rc<Node>) -> Result<(), LinkError> {
+        if let Some(old_parent) = child.parent.borrow().upgrade() {
+            if Rc::ptr_eq(&old_parent, new_parent) {
+                return Ok(());
+            }
+        }
+
+        if Node::is_ancestor_or_self(child, new_parent) {
+            return Err(LinkError::Cycle);
+        }
+        if new_parent.children.borrow().len() >= MAX_CHILDREN {
+            return Err(LinkError::ParentFull);
+        }
+
+        // both events are held back until the child is attached again, so a
+        // hook never sees the child as an orphan halfway through the move
+        let old_parent = Node::detach(child);
+        *child.parent.borrow_mut() = Rc::downgrade(new_parent);
+        new_parent.children.borrow_mut().push(Rc::clone(child));
+
+        if let Some(old_parent) = old_parent {
+            emit(LinkEvent::Unlinked { parent: old_parent, child: child.get_value() });
+        }
+        emit(LinkEvent::Linked { parent: new_parent.get_value(), child: child.get_value() });
+        Ok(())
+    }
+
+    // follows the parent links up from `node`, looking for `ancestor`
+    fn is_ancestor_or_self(ancestor: &Rc<Node>, node: &Rc<Node>) -> bool {
+        let mut current = Some(Rc::clone(node));
+        while let Some(node) = current {
+            if Rc::ptr_eq(&node, ancestor) {
+                return true;
+            }
+            current = node.parent.borrow().upgrade();
+        }
+        false
+    }
+
+    // removes the child from its parent's children and clears its parent,
+    // returning the old parent's value for the caller to report
+    fn detach(child: &Rc<Node>) -> Option<i32> {
+        let old_parent = child.parent.borrow().upgrade()?;
+        *child.parent.borrow_mut() = Weak::new();
+        old_parent.children.borrow_mut().retain(|c| !Rc::ptr_eq(c, child));
+        Some(old_parent.get_value())
+    }
+
+    /// unlinks the child at `index` from the parent and returns it, or
+    /// returns `None` if the parent has no child there
+    ///
+    /// The removed child becomes an orphan with its own children still
+    /// attached. Later children move down one place.
+    /// # Example
+    /// ```
+    /// let parent = min_heap::node::Node::new_orphan(1);
+    /// min_heap::node::Node::new_child(&parent, 2).unwrap();
+    /// min_heap::node::Node::new_child(&parent, 3).unwrap();
+    ///
+    /// let child = min_heap::node::Node::remove_child(&parent, 0).unwrap();
+    /// assert_eq!(child.get_value(), 2);
+    /// assert!(child.parent_weak().upgrade().is_none());
+    /// assert_eq!(parent.get_child_values(), vec![3])
+    /// ```
+    pub fn remove_child(parent: &Rc<Node>, index: usize) -> Option<Rc<Node>> {
+        let child = {
+            let mut children = parent.children.borrow_mut();
+            if index >= children.len() {
+                return None;
+            }
+            children.remove(index)
+        };
+
+        *child.parent.borrow_mut() = Weak::new();
+        emit(LinkEvent::Unlinked { parent: parent.get_value(), child: child.get_value() });
+        Some(child)
+    }
+
+    /// walks the tree below (and including) this node depth-first, calling
+    /// the visitor with each value, its depth and its index.
+    ///
+    /// The root of the walk has depth 0 and index 0. The index is the
+    /// node's position in the level-order (array) layout of the heap, so
+    /// the children of index `i` are at `2 * i + 1` and `2 * i + 2`.
+    ///
+    /// No node is borrowed while the visitor runs, so it may link, unlink
+    /// or swap nodes. A node's children are read just after the visitor
+    /// has seen it, and later changes to that list don't affect the walk.
+    /// # Example
+    /// ```
+    /// let root = min_heap::node::Node::new_orphan(1);
+    /// min_heap::node::Node::new_child(&root, 2).unwrap();
+    /// min_heap::node::Node::new_child(&root, 3).unwrap();
+    ///
+    /// let mut seen = vec![];
+    /// root.visit(|value, depth, index| seen.push((*value, depth, index)));
+    /// assert_eq!(seen, vec![(1, 0, 0), (2, 1, 1), (3, 1, 2)])
+    /// ```
+    pub fn visit<F: FnMut(&i32, usize, usize)>(&self, visitor: F) {
+        self.walk(&mut FnVisitor(visitor));
+    }
+
+    /// walks the tree below (and including) this node depth-first, calling
+    /// `enter` on the way down and `leave` once a node's children are done.
+    ///
+    /// Depths and indexes are the same as for `visit`.
+    /// # Example
+    /// ```
+    /// use min_heap::node::{Node, Visitor};
+    ///
+    /// struct Height(usize);
+    ///
+    /// impl Visitor for Height {
+    ///     fn enter(&mut self, _value: &i32, depth: usize, _index: usize) {
+    ///         self.0 = self.0.max(depth + 1);
+    ///     }
+    /// }
+    ///
+    /// let root = Node::new_orphan(1);
+    /// Node::new_child(&root, 2).unwrap();
+    ///
+    /// let mut height = Height(0);
+    /// root.walk(&mut height);
+    /// assert_eq!(height.0, 2)
+    /// ```
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) {
+        self.walk_from(visitor, 0, 0);
+    }
+
+    /// returns the values of the tree below (and including) this node in
+    /// level order, top to bottom and left to right
+    ///
+    /// For a complete tree this is the standard implicit array layout of a
+    /// heap, where the children of index `i` are at `2 * i + 1` and
+    /// `2 * i + 2`, and `from_level_order_vec` rebuilds the same tree.
+    /// # Example
+    /// ```
+    /// use min_heap::node::Node;
+    ///
+    /// let root = Node::new_orphan(1);
+    /// let left = Node::new_orphan(2);
+    /// Node::parent_child(&root, &left).unwrap();
+    /// Node::new_child(&root, 3).unwrap();
+    /// Node::new_child(&left, 4).unwrap();
+    ///
+    /// assert_eq!(root.to_level_order_vec(), vec![1, 2, 3, 4])
+    /// ```
+    pub fn to_level_order_vec(&self) -> Vec<i32> {
+        let mut values = vec![self.get_value()];
+        let mut level = self.children.borrow().clone();
+
+        while !level.is_empty() {
+            let mut next = vec![];
+            for node in level.iter() {
+                values.push(node.get_value());
+                next.extend(node.children.borrow().iter().cloned());
+            }
+            level = next;
+        }
+        values
+    }
+
+    /// builds a valid min-heap of `n` pseudo-random values drawn from
+    /// `value_range` and returns its root, or `None` if `n` is 0
+    ///
+    /// The same seed always gives the same tree, so load tests and
+    /// benchmarks can be reproduced. The generator is not suitable for
+    /// anything that needs unpredictable values.
+    ///
+    /// Panics if `value_range` is empty.
+    /// # Example
+    /// ```
+    /// use min_heap::node::Node;
+    ///
+    /// let root = Node::random(10, 42, 0..100).unwrap();
+    /// let values = root.to_level_order_vec();
+    ///
+    /// assert_eq!(values.len(), 10);
+    /// assert!(min_heap::slice::is_heap(&values));
+    /// assert_eq!(values, Node::random(10, 42, 0..100).unwrap().to_level_order_vec())
+    /// ```
+    pub fn random(n: usize, seed: u64, value_range: Range<i32>) -> Option<Rc<Node>> {
+        assert!(!value_range.is_empty(), "value range is empty");
+        let span = (i64::from(value_range.end) - i64::from(value_range.start)) as u64;
+
+        // splitmix64, which gives well spread values even from small seeds
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+
+        let mut values: Vec<i32> = (0..n)
+            .map(|_| (i64::from(value_range.start) + (next() % span) as i64) as i32)
+            .collect();
+        values.make_heap();
+        Node::from_level_order_vec(&values)
+    }
+
+    /// builds a complete tree from values in level order, the implicit
+    /// array layout of a heap, and returns its root, or `None` if there are
+    /// no values
+    /// # Example
+    /// ```
+    /// use min_heap::node::Node;
+    ///
+    /// let root = Node::from_level_order_vec(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(root.get_child_values(), vec![2, 3]);
+    /// assert_eq!(root.to_level_order_vec(), vec![1, 2, 3, 4])
+    /// ```
+    pub fn from_level_order_vec(values: &[i32]) -> Option<Rc<Node>> {
+        let nodes: Vec<Rc<Node>> = values.iter().map(|v| Node::new_orphan(*v)).collect();
+
+        for (i, node) in nodes.iter().enumerate().skip(1) {
+            let parent = parent_of(i).expect("only the root has no parent");
+            Node::parent_child(&nodes[parent], node)
+                .expect("level-order links always form a valid tree");
+        }
+        nodes.into_iter().next()
+    }
+
+    /// returns the sum of the values in the tree below (and including) this
+    /// node, widened to `i64` so it can't overflow
+    /// # Example
+    /// ```
+    /// let root = min_heap::node::Node::from_level_order_vec(&[1, 2, 3]).unwrap();
+    /// assert_eq!(root.sum(), 6)
+    /// ```
+    pub fn sum(&self) -> i64 {
+        let mut sum = 0;
+        self.visit(|value, _, _| sum += i64::from(*value));
+        sum
+    }
+
+    /// returns the mean of the values in the tree below (and including)
+    /// this node
+    /// # Example
+    /// ```
+    /// let root = min_heap::node::Node::from_level_order_vec(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(root.mean(), 2.5)
+    /// ```
+    pub fn mean(&self) -> f64 {
+        let mut count = 0usize;
+        self.visit(|_, _, _| count += 1);
+        self.sum() as f64 / count as f64
+    }
+
+    /// returns the smallest value in the tree below (and including) this
+    /// node, which is this node's own value if the tree is a valid heap
+    /// # Example
+    /// ```
+    /// let root = min_heap::node::Node::from_level_order_vec(&[4, 2, 9]).unwrap();
+    /// assert_eq!(root.min(), 2)
+    /// ```
+    pub fn min(&self) -> i32 {
+        let mut min = self.get_value();
+        self.visit(|value, _, _| min = min.min(*value));
+        min
+    }
+
+    /// returns the largest value in the tree below (and including) this node
+    /// # Example
+    /// ```
+    /// let root = min_heap::node::Node::from_level_order_vec(&[1, 7, 3]).unwrap();
+    /// assert_eq!(root.max(), 7)
+    /// ```
+    pub fn max(&self) -> i32 {
+        let mut max = self.get_value();
+        self.visit(|value, _, _| max = max.max(*value));
+        max
+    }
+
+    /// returns the height, leaf count, per-level node counts and how full
+    /// the last level is for the tree below (and including) this node
+    /// # Example
+    /// ```
+    /// let root = min_heap::node::Node::new_orphan(1);
+    /// min_heap::node::Node::new_child(&root, 2).unwrap();
+    /// min_heap::node::Node::new_child(&root, 3).unwrap();
+    ///
+    /// let stats = root.shape_stats();
+    /// assert_eq!(stats.height, 2);
+    /// assert_eq!(stats.leaves, 2);
+    /// assert_eq!(stats.level_counts, vec![1, 2]);
+    /// assert_eq!(stats.last_level_fill, 1.0)
+    /// ```
+    pub fn shape_stats(&self) -> ShapeStats {
+        struct Counter {
+            level_counts: Vec<usize>,
+        }
+
+        impl Visitor for Counter {
+            fn enter(&mut self, _value: &i32, depth: usize, _index: usize) {
+                if self.level_counts.len() == depth {
+                    self.level_counts.push(0);
+                }
+                self.level_counts[depth] += 1;
+            }
+        }
+
+        let mut counter = Counter { level_counts: vec![] };
+        self.walk(&mut counter);
+
+        let height = counter.level_counts.len();
+        let last = counter.level_counts[height - 1];
+        let last_level_fill = last as f64 / 2f64.powi(height as i32 - 1);
+
+        ShapeStats {
+            height,
+            leaves: self.count_leaves(),
+            last_level_fill,
+            level_counts: counter.level_counts,
+        }
+    }
+
+    /// returns a standalone SVG drawing of the tree below (and including)
+    /// this node, with a labelled circle for every node and a line from
+    /// each node to its parent
+    ///
+    /// Nodes are placed by their position in a complete binary tree, so
+    /// every level is twice as wide as the one above it.
+    /// # Example
+    /// ```
+    /// use min_heap::node::{Node, SvgOptions};
+    ///
+    /// let root = Node::from_level_order_vec(&[1, 2, 3]).unwrap();
+    /// let svg = root.to_svg(&SvgOptions::default());
+    ///
+    /// assert!(svg.starts_with("<svg"));
+    /// assert_eq!(svg.matches("<circle").count(), 3);
+    /// assert_eq!(svg.matches("<line").count(), 2)
+    /// ```
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        struct Layout<'a> {
+            options: &'a SvgOptions,
+            width: f64,
+            path: Vec<(f64, f64)>,
+            edges: String,
+            nodes: String,
+        }
+
+        impl Visitor for Layout<'_> {
+            fn enter(&mut self, value: &i32, depth: usize, index: usize) {
+                let slots = 2f64.powi(depth as i32);
+                let offset = index as f64 - (slots - 1.0);
+                let x = (offset + 0.5) * self.width / slots;
+                let y = self.options.node_radius * 2.0 + depth as f64 * self.options.level_height;
+
+                if let Some(&(px, py)) = self.path.last() {
+                    self.edges.push_str(&format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>\n",
+                        px, py, x, y
+                    ));
+                }
+                self.nodes.push_str(&format!(
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"white\" stroke=\"black\"/>\n",
+                    x, y, self.options.node_radius
+                ));
+                self.nodes.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-size=\"{}\">{}</text>\n",
+                    x, y, self.options.font_size, value
+                ));
+                self.path.push((x, y));
+            }
+
+            fn leave(&mut self, _value: &i32, _depth: usize, _index: usize) {
+                self.path.pop();
+            }
+        }
+
+        let height = self.shape_stats().height;
+        let width = 2f64.powi(height as i32 - 1) * options.node_spacing;
+        let mut layout = Layout {
+            options,
+            width,
+            path: vec![],
+            edges: String::new(),
+            nodes: String::new(),
+        };
+        self.walk(&mut layout);
+
+        let svg_height = options.node_radius * 4.0 + (height - 1) as f64 * options.level_height;
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}{}</svg>\n",
+            layout.edges,
+            layout.nodes,
+            w = width,
+            h = svg_height
+        )
+    }
+
+    /// prints the tree below (and including) this node to stdout, one node
+    /// per line indented by depth, with the root in cyan and any node
+    /// smaller than its parent in red
+    ///
+    /// Only available with the `cli` feature.
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "cli")]
+    /// # {
+    /// let root = min_heap::node::Node::from_level_order_vec(&[1, 5, 3, 2]).unwrap();
+    /// // 2 is printed in red, since it is below 5
+    /// root.print_colored()
+    /// # }
+    /// ```
+    #[cfg(feature = "cli")]
+    pub fn print_colored(&self) {
+        print!("{}", self.colored_lines());
+    }
+
+    #[cfg(feature = "cli")]
+    fn colored_lines(&self) -> String {
+        const ROOT: &str = "\x1b[1;36m";
+        const VIOLATION: &str = "\x1b[1;31m";
+        const RESET: &str = "\x1b[0m";
+
+        struct Printer {
+            parents: Vec<i32>,
+            out: String,
+        }
+
+        impl Visitor for Printer {
+            fn enter(&mut self, value: &i32, depth: usize, _index: usize) {
+                let color = match self.parents.last() {
+                    None => Some(ROOT),
+                    Some(parent) if value < parent => Some(VIOLATION),
+                    Some(_) => None,
+                };
+                let indent = "  ".repeat(depth);
+                match color {
+                    Some(color) => self.out.push_str(&format!("{}{}{}{}\n", indent, color, value, RESET)),
+                    None => self.out.push_str(&format!("{}{}\n", indent, value)),
+                }
+                self.parents.push(*value);
+            }
+
+            fn leave(&mut self, _value: &i32, _depth: usize, _index: usize) {
+                self.parents.pop();
+            }
+        }
+
+        let mut printer = Printer { parents: vec![], out: String::new() };
+        self.walk(&mut printer);
+        printer.out
+    }
+
+    fn count_leaves(&self) -> usize {
+        let children = self.children.borrow();
+        if children.is_empty() {
+            return 1;
+        }
+        children.iter().map(|child| child.count_leaves()).sum()
+    }
+
+    // the visitor may relink nodes, so no borrow is held while it runs:
+    // the children are copied out once `enter` has returned
+    fn walk_from<V: Visitor>(&self, visitor: &mut V, depth: usize, index: usize) {
+        let value = self.value.get();
+        visitor.enter(&value, depth, index);
+
+        // indexes saturate rather than overflow on trees too deep to be
+        // complete, such as long chains of only children
+        let children = self.children.borrow().clone();
+        for (i, child) in children.iter().enumerate() {
+            let child_index = index.saturating_mul(2).saturating_add(1 + i);
+            child.walk_from(visitor, depth + 1, child_index);
+        }
+
+        visitor.leave(&value, depth, index);
+    }
+}
+
+/// A position in a tree of nodes that can be moved between parents and
+/// children without handling the `Rc`/`Weak` links directly.
+///
+/// A move that has nowhere to go returns false and leaves the cursor where
+/// it was.
+/// # Example
+/// ```
+/// use min_heap::node::{Cursor, Node};
+///
+/// let root = Node::new_orphan(1);
+/// Node::new_child(&root, 2).unwrap();
+/// Node::new_child(&root, 3).unwrap();
+///
+/// let mut cursor = Cursor::new(&root);
+/// assert!(cursor.move_to_right());
+/// assert_eq!(cursor.value(), 3);
+/// assert!(!cursor.move_to_left());
+/// assert!(cursor.move_to_parent());
+/// assert_eq!(cursor.value(), 1)
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    current: Rc<Node>,
+}
+
+impl Cursor {
+    /// creates a cursor pointing at the given node
+    pub fn new(node: &Rc<Node>) -> Cursor {
+        Cursor { current: Rc::clone(node) }
+    }
+
+    /// returns the value of the node under the cursor
+    pub fn value(&self) -> i32 {
+        self.current.get_value()
+    }
+
+    /// moves to the parent node, if there is one and it is still alive
+    pub fn move_to_parent(&mut self) -> bool {
+        let parent = self.current.parent.borrow().upgrade();
+        self.move_to(parent)
+    }
+
+    /// moves to the first child, if there is one
+    pub fn move_to_left(&mut self) -> bool {
+        let child = self.current.children.borrow().first().cloned();
+        self.move_to(child)
+    }
+
+    /// moves to the second child, if there is one
+    pub fn move_to_right(&mut self) -> bool {
+        let child = self.current.children.borrow().get(1).cloned();
+        self.move_to(child)
+    }
+
+    fn move_to(&mut self, node: Option<Rc<Node>>) -> bool {
+        match node {
+            None => false,
+            Some(node) => {
+                self.current = node;
+                true
+            }
+        }
+    }
+}
+
+/// The shape of a tree of nodes, as returned by `Node::shape_stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeStats {
+    /// the number of levels in the tree, so a lone node has height 1
+    pub height: usize,
+    /// the number of nodes without children
+    pub leaves: usize,
+    /// the share of the last level's binary slots that hold a node, from 0 to 1
+    pub last_level_fill: f64,
+    /// the number of nodes on each level, starting with the root's
+    pub level_counts: Vec<usize>,
+}
+
+/// Sizes used by `Node::to_svg`, all in SVG user units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// the radius of each node's circle
+    pub node_radius: f64,
+    /// the horizontal distance between neighbouring nodes on the last level
+    pub node_spacing: f64,
+    /// the vertical distance between levels
+    pub level_height: f64,
+    /// the size of the value labels
+    pub font_size: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            node_radius: 16.0,
+            node_spacing: 40.0,
+            level_height: 56.0,
+            font_size: 14.0,
+        }
+    }
+}
+
+/// Callbacks for walking a tree of nodes with `Node::walk`.
+///
+/// Only values are handed out, so a visitor never has to deal with the
+/// `Rc`/`Weak` links between nodes.
+pub trait Visitor {
+    /// called when the walk reaches a node, before any of its children
+    fn enter(&mut self, value: &i32, depth: usize, index: usize);
+
+    /// called after all of a node's children have been walked
+    fn leave(&mut self, _value: &i32, _depth: usize, _index: usize) {}
+}
+
+// lets `Node::visit` reuse the walk with a plain closure
+struct FnVisitor<F>(F);
+
+impl<F: FnMut(&i32, usize, usize)> Visitor for FnVisitor<F> {
+    fn enter(&mut self, value: &i32, depth: usize, index: usize) {
+        (self.0)(value, depth, index)
     }
 }
 
@@ -128,14 +977,112 @@ mod tests {
         let leaf = Node::new_orphan(3);
         let branch = Node::new_orphan(5);
 
-        Node::parent_child(&branch, &leaf);
+        Node::parent_child(&branch, &leaf).unwrap();
 
         let branch_child = branch.children.borrow();
         match leaf.parent.borrow().upgrade() {
             None => panic!("child does not have a parent value"),
             Some(x) => assert_eq!(x.get_value(), branch.get_value())
         };
-        assert_eq!(branch_child[0].value, leaf.get_value());
+        assert_eq!(branch_child[0].get_value(), leaf.get_value());
+    }
+
+    #[test]
+    fn parent_child_rejects_duplicate() {
+        let parent = Node::new_orphan(1);
+        let child = Node::new_orphan(2);
+        Node::parent_child(&parent, &child).unwrap();
+
+        assert_eq!(Node::parent_child(&parent, &child), Err(LinkError::Duplicate));
+        assert_eq!(parent.get_child_values(), vec![2]);
+        assert_eq!(Rc::strong_count(&child), 2)
+    }
+
+    #[test]
+    fn parent_child_rejects_cycles() {
+        let root = Node::new_orphan(1);
+        let middle = Node::new_orphan(2);
+        let leaf = Node::new_orphan(3);
+        Node::parent_child(&root, &middle).unwrap();
+        Node::parent_child(&middle, &leaf).unwrap();
+
+        assert_eq!(Node::parent_child(&leaf, &root), Err(LinkError::Cycle));
+        assert_eq!(Node::parent_child(&root, &root), Err(LinkError::Cycle));
+        assert!(leaf.get_child_values().is_empty());
+
+        // nothing should be holding the root besides this test
+        assert_eq!(Rc::strong_count(&root), 1)
+    }
+
+    #[test]
+    fn reparent_rejects_cycles() {
+        let root = Node::new_orphan(1);
+        let middle = Node::new_orphan(2);
+        let leaf = Node::new_orphan(3);
+        Node::parent_child(&root, &middle).unwrap();
+        Node::parent_child(&middle, &leaf).unwrap();
+
+        assert_eq!(Node::reparent(&middle, &leaf), Err(LinkError::Cycle));
+        assert_eq!(Node::reparent(&middle, &middle), Err(LinkError::Cycle));
+        assert_eq!(root.get_child_values(), vec![2]);
+        assert_eq!(middle.get_child_values(), vec![3])
+    }
+
+    // every link operation reports what it changed, and nothing is
+    // reported once the hook is cleared
+    #[test]
+    fn link_hook_events() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let events = Rc::new(RefCell::new(vec![]));
+        let seen = Rc::clone(&events);
+        set_link_hook(move |event| seen.borrow_mut().push(*event));
+
+        let first = Node::new_orphan(1);
+        let second = Node::new_orphan(2);
+        let child = Node::new_orphan(3);
+        Node::parent_child(&first, &child).unwrap();
+        Node::reparent(&child, &second).unwrap();
+        Node::new_child(&child, 4).unwrap();
+        Node::remove_child(&second, 0);
+
+        clear_link_hook();
+        Node::new_child(&first, 5).unwrap();
+
+        assert_eq!(*events.borrow(), vec![
+            LinkEvent::Linked { parent: 1, child: 3 },
+            LinkEvent::Unlinked { parent: 1, child: 3 },
+            LinkEvent::Linked { parent: 2, child: 3 },
+            LinkEvent::Linked { parent: 3, child: 4 },
+            LinkEvent::Unlinked { parent: 2, child: 3 },
+        ]);
+    }
+
+    #[test]
+    fn parent_child_rejects_second_parent() {
+        let first = Node::new_orphan(1);
+        let second = Node::new_orphan(2);
+        let child = Node::new_orphan(3);
+        Node::parent_child(&first, &child).unwrap();
+
+        assert_eq!(Node::parent_child(&second, &child), Err(LinkError::AlreadyHasParent));
+        assert!(second.get_child_values().is_empty());
+
+        // once the old parent is gone the child is an orphan again
+        drop(first);
+        assert_eq!(Node::parent_child(&second, &child), Ok(()))
+    }
+
+    #[test]
+    fn links_reject_full_parent() {
+        let parent = Node::new_orphan(1);
+        Node::new_child(&parent, 2).unwrap();
+        Node::new_child(&parent, 3).unwrap();
+
+        assert_eq!(Node::new_child(&parent, 4), Err(LinkError::ParentFull));
+        assert_eq!(Node::parent_child(&parent, &Node::new_orphan(5)), Err(LinkError::ParentFull));
+        assert_eq!(parent.get_child_values(), vec![2, 3])
     }
 
     #[test]
@@ -144,8 +1091,8 @@ mod tests {
         let node2 = Node::new_orphan(2);
         let node3 = Node::new_orphan(3);
         
-        Node::parent_child(&node1, &node2);
-        Node::parent_child(&node1, &node3);
+        Node::parent_child(&node1, &node2).unwrap();
+        Node::parent_child(&node1, &node3).unwrap();
 
         let arr : Vec<i32> = vec![2, 3];
         assert_eq!(arr, node1.get_child_values())
@@ -155,7 +1102,498 @@ mod tests {
     fn one_new_child() {
         let parent = Node::new_orphan(5);
         let val = vec![24];
-        Node::new_child(&parent, 24);
+        Node::new_child(&parent, 24).unwrap();
         assert_eq!(parent.get_child_values(), val)
     }
+
+    #[test]
+    fn remove_child_unlinks_both_ways() {
+        let parent = Node::new_orphan(1);
+        Node::new_child(&parent, 2).unwrap();
+        Node::new_child(&parent, 3).unwrap();
+
+        let child = Node::remove_child(&parent, 1).unwrap();
+        assert_eq!(child.get_value(), 3);
+        assert!(child.parent.borrow().upgrade().is_none());
+        assert_eq!(parent.get_child_values(), vec![2]);
+
+        // the parent no longer holds the child alive
+        assert_eq!(Rc::strong_count(&child), 1)
+    }
+
+    #[test]
+    fn remove_child_keeps_grandchildren() {
+        let parent = Node::new_orphan(1);
+        Node::new_child(&parent, 2).unwrap();
+        let child = Rc::clone(&parent.children.borrow()[0]);
+        Node::new_child(&child, 4).unwrap();
+        drop(child);
+
+        let child = Node::remove_child(&parent, 0).unwrap();
+        assert_eq!(child.get_child_values(), vec![4]);
+        assert!(Node::remove_child(&parent, 0).is_none())
+    }
+
+    #[test]
+    fn reparent_moves_child() {
+        let old_parent = Node::new_orphan(1);
+        let new_parent = Node::new_orphan(2);
+        Node::new_child(&old_parent, 3).unwrap();
+        Node::new_child(&old_parent, 4).unwrap();
+        let child = Rc::clone(&old_parent.children.borrow()[0]);
+
+        Node::reparent(&child, &new_parent).unwrap();
+        assert_eq!(old_parent.get_child_values(), vec![4]);
+        assert_eq!(new_parent.get_child_values(), vec![3]);
+        match child.parent.borrow().upgrade() {
+            None => panic!("child lost its parent"),
+            Some(x) => assert!(Rc::ptr_eq(&x, &new_parent))
+        };
+
+        // only the new parent and this test hold the child
+        assert_eq!(Rc::strong_count(&child), 2)
+    }
+
+    #[test]
+    fn reparent_orphan() {
+        let parent = Node::new_orphan(1);
+        let child = Node::new_orphan(2);
+
+        Node::reparent(&child, &parent).unwrap();
+        assert_eq!(parent.get_child_values(), vec![2])
+    }
+
+    // a full parent is rejected before the child is detached
+    #[test]
+    fn reparent_to_full_parent() {
+        let full = Node::new_orphan(1);
+        Node::new_child(&full, 2).unwrap();
+        Node::new_child(&full, 3).unwrap();
+        let old_parent = Node::new_orphan(4);
+        let child = Node::new_orphan(5);
+        Node::parent_child(&old_parent, &child).unwrap();
+
+        assert_eq!(Node::reparent(&child, &full), Err(LinkError::ParentFull));
+        assert_eq!(old_parent.get_child_values(), vec![5]);
+        assert_eq!(full.get_child_values(), vec![2, 3])
+    }
+
+    #[test]
+    fn reparent_to_same_parent() {
+        let parent = Node::new_orphan(1);
+        Node::new_child(&parent, 2).unwrap();
+        Node::new_child(&parent, 3).unwrap();
+        let child = Rc::clone(&parent.children.borrow()[0]);
+
+        assert_eq!(Node::reparent(&child, &parent), Ok(()));
+        assert_eq!(parent.get_child_values(), vec![2, 3])
+    }
+
+    #[test]
+    fn swap_values() {
+        let parent = Node::new_orphan(9);
+        Node::new_child(&parent, 2).unwrap();
+        let child = Rc::clone(&parent.children.borrow()[0]);
+
+        Node::swap(&parent, &child);
+        assert_eq!(parent.get_value(), 2);
+        assert_eq!(parent.get_child_values(), vec![9])
+    }
+
+    // none of the link operations may panic from a borrow the walk holds
+    #[test]
+    fn relink_during_walk() {
+        let root = Node::new_orphan(1);
+        Node::new_child(&root, 2).unwrap();
+        let child = Rc::clone(&root.children.borrow()[0]);
+        let other = Node::new_orphan(9);
+
+        let mut seen = vec![];
+        root.visit(|value, _, _| {
+            seen.push(*value);
+            match *value {
+                1 => Node::new_child(&root, 3).unwrap(),
+                2 => {
+                    Node::new_child(&child, 4).unwrap();
+                    Node::remove_child(&root, 0);
+                    Node::reparent(&child, &other).unwrap();
+                }
+                _ => {}
+            }
+        });
+
+        // the root's children are copied once `enter(1)` has returned, so 3,
+        // added inside it, is walked; the later changes to the root's
+        // children don't affect that copy
+        assert_eq!(seen, vec![1, 2, 4, 3]);
+        assert_eq!(root.get_child_values(), vec![3]);
+        assert_eq!(other.get_child_values(), vec![2])
+    }
+
+    // the hook may link nodes itself, which reports events from inside it
+    #[test]
+    fn link_from_hook() {
+        let spare = Node::new_orphan(0);
+        let inner = Rc::clone(&spare);
+        set_link_hook(move |event| {
+            if let LinkEvent::Linked { child: 2, .. } = event {
+                Node::new_child(&inner, 7).unwrap();
+            }
+        });
+
+        let root = Node::new_orphan(1);
+        Node::new_child(&root, 2).unwrap();
+        clear_link_hook();
+
+        assert_eq!(spare.get_child_values(), vec![7])
+    }
+
+    // a hook that tries to relink the child while it is being moved must
+    // find it already attached to its new parent
+    #[test]
+    fn relink_from_hook_during_reparent() {
+        let first = Node::new_orphan(1);
+        let second = Node::new_orphan(2);
+        let third = Node::new_orphan(3);
+        let child = Node::new_orphan(4);
+        Node::parent_child(&first, &child).unwrap();
+
+        let results = Rc::new(RefCell::new(vec![]));
+        let (seen, target, moved) = (Rc::clone(&results), Rc::clone(&third), Rc::clone(&child));
+        set_link_hook(move |event| {
+            if let LinkEvent::Unlinked { .. } = event {
+                seen.borrow_mut().push(Node::parent_child(&target, &moved));
+            }
+        });
+        Node::reparent(&child, &second).unwrap();
+        clear_link_hook();
+
+        assert_eq!(*results.borrow(), vec![Err(LinkError::AlreadyHasParent)]);
+        assert!(first.get_child_values().is_empty());
+        assert_eq!(second.get_child_values(), vec![4]);
+        assert!(third.get_child_values().is_empty());
+        // held by this test and the new parent only
+        assert_eq!(Rc::strong_count(&child), 2);
+    }
+
+    // the hook may replace or clear itself without a borrow panic
+    #[test]
+    fn hook_replaces_itself() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let events = Rc::new(RefCell::new(vec![]));
+        let seen = Rc::clone(&events);
+        set_link_hook(move |event| {
+            seen.borrow_mut().push(*event);
+            let seen = Rc::clone(&seen);
+            set_link_hook(move |event| {
+                seen.borrow_mut().push(*event);
+                clear_link_hook();
+            });
+        });
+
+        let root = Node::new_orphan(1);
+        Node::new_child(&root, 2).unwrap();
+        Node::new_child(&root, 3).unwrap();
+        let child = root.children.borrow()[0].clone();
+        Node::new_child(&child, 4).unwrap();
+
+        assert_eq!(*events.borrow(), vec![
+            LinkEvent::Linked { parent: 1, child: 2 },
+            LinkEvent::Linked { parent: 1, child: 3 },
+        ]);
+    }
+
+    // swapping while a walk holds the children borrowed must not panic
+    #[test]
+    fn swap_during_walk() {
+        let root = Node::new_orphan(5);
+        Node::new_child(&root, 1).unwrap();
+        let child = Rc::clone(&root.children.borrow()[0]);
+
+        root.visit(|_, depth, _| {
+            if depth == 1 {
+                Node::swap(&root, &child);
+            }
+        });
+        assert_eq!(root.get_value(), 1);
+        assert_eq!(child.get_value(), 5)
+    }
+
+    #[test]
+    fn visit_depths_and_indexes() {
+        let root = Node::new_orphan(1);
+        let left = Node::new_orphan(2);
+        Node::parent_child(&root, &left).unwrap();
+        Node::new_child(&root, 3).unwrap();
+        Node::new_child(&left, 4).unwrap();
+        Node::new_child(&left, 5).unwrap();
+
+        let mut seen = vec![];
+        root.visit(|value, depth, index| seen.push((*value, depth, index)));
+        assert_eq!(seen, vec![(1, 0, 0), (2, 1, 1), (4, 2, 3), (5, 2, 4), (3, 1, 2)])
+    }
+
+    #[test]
+    fn level_order_round_trip() {
+        let values: Vec<i32> = (1..=10).collect();
+        let root = Node::from_level_order_vec(&values).unwrap();
+        assert_eq!(root.to_level_order_vec(), values);
+
+        // the indexes the walk reports match the array positions
+        root.visit(|value, _, index| assert_eq!(values[index], *value));
+        assert_eq!(root.shape_stats().level_counts, vec![1, 2, 4, 3])
+    }
+
+    #[test]
+    fn level_order_edge_cases() {
+        assert!(Node::from_level_order_vec(&[]).is_none());
+
+        let root = Node::from_level_order_vec(&[7]).unwrap();
+        assert_eq!(root.to_level_order_vec(), vec![7]);
+        assert!(root.get_child_values().is_empty())
+    }
+
+    // indexes past what usize can address saturate instead of panicking
+    #[test]
+    fn visit_deep_chain() {
+        let root = Node::new_orphan(0);
+        let mut tail = Rc::clone(&root);
+        for i in 1..100 {
+            Node::new_child(&tail, i).unwrap();
+            let next = Rc::clone(&tail.children.borrow()[0]);
+            tail = next;
+        }
+
+        let mut last_index = 0;
+        root.visit(|_, _, index| last_index = index);
+        assert_eq!(last_index, usize::MAX)
+    }
+
+    #[test]
+    fn aggregates() {
+        let root = Node::from_level_order_vec(&[3, 8, 5, -2, 10]).unwrap();
+        assert_eq!(root.sum(), 24);
+        assert_eq!(root.mean(), 4.8);
+        assert_eq!(root.min(), -2);
+        assert_eq!(root.max(), 10);
+
+        // a subtree only counts its own values
+        let left = Rc::clone(&root.children.borrow()[0]);
+        assert_eq!(left.sum(), 16);
+        assert_eq!(left.min(), -2)
+    }
+
+    #[test]
+    fn sum_does_not_overflow() {
+        let root = Node::from_level_order_vec(&[i32::MAX, i32::MAX, i32::MAX]).unwrap();
+        assert_eq!(root.sum(), 3 * i64::from(i32::MAX));
+        assert_eq!(root.mean(), f64::from(i32::MAX))
+    }
+
+    #[test]
+    fn shape_stats_partial_last_level() {
+        let root = Node::new_orphan(1);
+        let left = Node::new_orphan(2);
+        Node::parent_child(&root, &left).unwrap();
+        Node::new_child(&root, 3).unwrap();
+        Node::new_child(&left, 4).unwrap();
+
+        let stats = root.shape_stats();
+        assert_eq!(stats.height, 3);
+        assert_eq!(stats.leaves, 2);
+        assert_eq!(stats.level_counts, vec![1, 2, 1]);
+        assert_eq!(stats.last_level_fill, 0.25)
+    }
+
+    #[test]
+    fn shape_stats_lone_node() {
+        let stats = Node::new_orphan(1).shape_stats();
+        assert_eq!(stats, ShapeStats {
+            height: 1,
+            leaves: 1,
+            last_level_fill: 1.0,
+            level_counts: vec![1],
+        })
+    }
+
+    #[test]
+    fn random_heaps() {
+        assert!(Node::random(0, 1, 0..10).is_none());
+
+        let values = Node::random(100, 7, -5..5).unwrap().to_level_order_vec();
+        assert_eq!(values.len(), 100);
+        assert!(crate::slice::is_heap(&values));
+        assert!(values.iter().all(|v| (-5..5).contains(v)));
+        assert_ne!(values, Node::random(100, 8, -5..5).unwrap().to_level_order_vec());
+
+        // the full i32 range must not overflow the span
+        let wide = Node::random(20, 3, i32::MIN..i32::MAX).unwrap().to_level_order_vec();
+        assert!(crate::slice::is_heap(&wide))
+    }
+
+    #[test]
+    #[should_panic(expected = "value range is empty")]
+    fn random_empty_range() {
+        Node::random(1, 1, 3..3);
+    }
+
+    // each node sits in its own slot of its level, and its edge starts at
+    // its parent's centre
+    #[test]
+    fn svg_layout() {
+        let options = SvgOptions {
+            node_radius: 10.0,
+            node_spacing: 30.0,
+            level_height: 50.0,
+            font_size: 12.0,
+        };
+        let root = Node::from_level_order_vec(&[1, 2, 3, 4]).unwrap();
+        let svg = root.to_svg(&options);
+
+        assert!(svg.contains("width=\"120\" height=\"140\""));
+        assert!(svg.contains("<circle cx=\"60\" cy=\"20\" r=\"10\""));
+        assert!(svg.contains("<circle cx=\"30\" cy=\"70\" r=\"10\""));
+        assert!(svg.contains("<circle cx=\"90\" cy=\"70\" r=\"10\""));
+        assert!(svg.contains("<circle cx=\"15\" cy=\"120\" r=\"10\""));
+        assert!(svg.contains("<line x1=\"30\" y1=\"70\" x2=\"15\" y2=\"120\""));
+        assert!(svg.contains(">4</text>"));
+        assert!(svg.ends_with("</svg>\n"))
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn colored_lines_mark_root_and_violations() {
+        let root = Node::from_level_order_vec(&[1, 5, 3, 2]).unwrap();
+        assert_eq!(root.colored_lines(), concat!(
+            "\x1b[1;36m1\x1b[0m\n",
+            "  5\n",
+            "    \x1b[1;31m2\x1b[0m\n",
+            "  3\n",
+        ))
+    }
+
+    #[test]
+    fn cursor_moves() {
+        let root = Node::new_orphan(1);
+        let left = Node::new_orphan(2);
+        Node::parent_child(&root, &left).unwrap();
+        Node::new_child(&root, 3).unwrap();
+        Node::new_child(&left, 4).unwrap();
+
+        let mut cursor = Cursor::new(&root);
+        assert!(!cursor.move_to_parent());
+        assert!(cursor.move_to_left());
+        assert_eq!(cursor.value(), 2);
+        assert!(cursor.move_to_left());
+        assert_eq!(cursor.value(), 4);
+        assert!(!cursor.move_to_right());
+        assert_eq!(cursor.value(), 4);
+        assert!(cursor.move_to_parent());
+        assert!(cursor.move_to_parent());
+        assert!(cursor.move_to_right());
+        assert_eq!(cursor.value(), 3)
+    }
+
+    // the cursor only holds a weak link upwards, like the nodes themselves
+    #[test]
+    fn cursor_parent_dropped() {
+        let root = Node::new_orphan(1);
+        Node::new_child(&root, 2).unwrap();
+
+        let mut cursor = Cursor::new(&root);
+        cursor.move_to_left();
+        drop(root);
+
+        assert!(!cursor.move_to_parent());
+        assert_eq!(cursor.value(), 2)
+    }
+
+    // observers must not keep any part of the tree alive
+    #[test]
+    fn weak_observers_do_not_extend_lifetimes() {
+        let parent = Node::new_orphan(1);
+        Node::new_child(&parent, 2).unwrap();
+        let child = Rc::clone(&parent.children.borrow()[0]);
+
+        let observed_parent = child.parent_weak();
+        let observed_child = Node::downgrade(&child);
+        assert_eq!(Rc::strong_count(&parent), 1);
+        assert_eq!(Rc::strong_count(&child), 2);
+
+        drop(child);
+        drop(parent);
+        assert!(observed_parent.upgrade().is_none());
+        assert!(observed_child.upgrade().is_none())
+    }
+
+    #[cfg(feature = "sync-nodes")]
+    #[test]
+    fn subtree_sent_to_thread() {
+        let root = Node::new_orphan(1);
+        Node::new_child(&root, 2).unwrap();
+        let child = Rc::clone(&root.children.borrow()[0]);
+        Node::new_child(&child, 3).unwrap();
+
+        let values = std::thread::spawn(move || {
+            let mut values = vec![];
+            child.visit(|value, _, _| values.push(*value));
+            values
+        })
+        .join()
+        .unwrap();
+        assert_eq!(values, vec![2, 3])
+    }
+
+    // overlapping swaps on several threads must only ever move values
+    // around, never copy one over another
+    #[cfg(feature = "sync-nodes")]
+    #[test]
+    fn concurrent_swaps_keep_values() {
+        let nodes: Vec<Rc<Node>> = (1..=3).map(Node::new_orphan).collect();
+
+        let threads: Vec<_> = [(0, 1), (0, 2), (1, 2)]
+            .iter()
+            .map(|&(a, b)| {
+                let (a, b) = (Rc::clone(&nodes[a]), Rc::clone(&nodes[b]));
+                std::thread::spawn(move || {
+                    for _ in 0..10_000 {
+                        Node::swap(&a, &b);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mut values: Vec<i32> = nodes.iter().map(|n| n.get_value()).collect();
+        values.sort();
+        assert_eq!(values, vec![1, 2, 3])
+    }
+
+    // leave should only fire once every child has been entered and left
+    #[test]
+    fn walk_enter_and_leave_order() {
+        struct Trace(Vec<String>);
+
+        impl Visitor for Trace {
+            fn enter(&mut self, value: &i32, _depth: usize, _index: usize) {
+                self.0.push(format!("enter {}", value));
+            }
+
+            fn leave(&mut self, value: &i32, _depth: usize, _index: usize) {
+                self.0.push(format!("leave {}", value));
+            }
+        }
+
+        let root = Node::new_orphan(1);
+        Node::new_child(&root, 2).unwrap();
+        Node::new_child(&root, 3).unwrap();
+
+        let mut trace = Trace(vec![]);
+        root.walk(&mut trace);
+        assert_eq!(trace.0, vec!["enter 1", "enter 2", "leave 2", "enter 3", "leave 3", "leave 1"])
+    }
 }
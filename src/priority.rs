@@ -0,0 +1,163 @@
+use core::fmt;
+use core::slice;
+
+use crate::fixed::{Full, StaticMinHeap};
+
+/// A fixed-capacity min-heap of `N` entries, each a priority `P` paired
+/// with a payload `V`.
+///
+/// Only the priorities are ever compared, so the payload needs no traits
+/// at all: not `Ord`, not even `PartialEq`. Entries with equal priorities
+/// come out in no particular order.
+/// # Example
+/// ```
+/// use min_heap::priority::PriorityHeap;
+///
+/// // closures can't be compared, but they can still be scheduled
+/// let mut jobs: PriorityHeap<u32, Box<dyn Fn() -> &'static str>, 4> = PriorityHeap::new();
+/// jobs.push(2, Box::new(|| "later")).ok().unwrap();
+/// jobs.push(1, Box::new(|| "first")).ok().unwrap();
+///
+/// let (priority, job) = jobs.pop().unwrap();
+/// assert_eq!((priority, job()), (1, "first"))
+/// ```
+pub struct PriorityHeap<P, V, const N: usize> {
+    heap: StaticMinHeap<(P, V), N>,
+}
+
+impl<P, V, const N: usize> PriorityHeap<P, V, N> {
+    /// creates an empty heap with room for `N` entries
+    pub const fn new() -> Self {
+        PriorityHeap { heap: StaticMinHeap::new() }
+    }
+
+    /// returns the number of entries in the heap
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// returns true if the heap holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// returns true if another push would fail
+    pub fn is_full(&self) -> bool {
+        self.heap.is_full()
+    }
+
+    /// returns the number of entries the heap can hold, which is always `N`
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// removes every entry from the heap
+    pub fn clear(&mut self) {
+        self.heap.clear()
+    }
+
+    /// returns an iterator over the entries in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, (P, V)> {
+        self.heap.as_slice().iter()
+    }
+}
+
+impl<P: Ord, V, const N: usize> PriorityHeap<P, V, N> {
+    /// adds an entry, or gives it back in `Err(Full)` if the heap has no
+    /// room left
+    pub fn push(&mut self, priority: P, value: V) -> Result<(), Full<(P, V)>> {
+        self.heap.push_by((priority, value), &above)
+    }
+
+    /// removes and returns the entry with the smallest priority, or `None`
+    /// if the heap is empty
+    pub fn pop(&mut self) -> Option<(P, V)> {
+        self.heap.pop_by(&above)
+    }
+
+    /// returns the entry with the smallest priority without removing it
+    pub fn peek(&self) -> Option<(&P, &V)> {
+        self.heap.peek().map(|(priority, value)| (priority, value))
+    }
+
+    /// removes and returns the entry with the smallest priority only if
+    /// that priority is no greater than `bound`
+    /// # Example
+    /// ```
+    /// let mut heap = min_heap::priority::PriorityHeap::<u64, &str, 4>::new();
+    /// heap.push(30, "thirty").unwrap();
+    ///
+    /// assert_eq!(heap.pop_if_le(&10), None);
+    /// assert_eq!(heap.pop_if_le(&30), Some((30, "thirty")))
+    /// ```
+    pub fn pop_if_le(&mut self, bound: &P) -> Option<(P, V)> {
+        match self.heap.peek() {
+            Some((priority, _)) if priority <= bound => self.pop(),
+            _ => None,
+        }
+    }
+}
+
+// entries are ordered by priority alone, so payloads are never compared
+fn above<P: Ord, V>(a: &(P, V), b: &(P, V)) -> bool {
+    a.0 < b.0
+}
+
+impl<P, V, const N: usize> Default for PriorityHeap<P, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for PriorityHeap<P, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a payload with no traits at all
+    struct Opaque(&'static str);
+
+    #[test]
+    fn pops_by_priority_only() {
+        let mut heap = PriorityHeap::<i32, Opaque, 8>::new();
+        for &(priority, name) in [(5, "e"), (1, "a"), (4, "d"), (2, "b"), (3, "c")].iter() {
+            assert!(heap.push(priority, Opaque(name)).is_ok());
+        }
+        assert_eq!(heap.peek().map(|(p, v)| (*p, v.0)), Some((1, "a")));
+
+        let mut popped = vec![];
+        while let Some((priority, value)) = heap.pop() {
+            popped.push((priority, value.0));
+        }
+        assert_eq!(popped, vec![(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")])
+    }
+
+    #[test]
+    fn full_gives_back_entry() {
+        let mut heap = PriorityHeap::<i32, Opaque, 1>::new();
+        assert!(heap.push(1, Opaque("kept")).is_ok());
+
+        match heap.push(0, Opaque("rejected")) {
+            Err(Full((priority, value))) => assert_eq!((priority, value.0), (0, "rejected")),
+            Ok(()) => panic!("push into a full heap succeeded"),
+        }
+        assert_eq!(heap.len(), 1)
+    }
+
+    #[test]
+    fn equal_priorities_all_come_out() {
+        let mut heap = PriorityHeap::<i32, Opaque, 4>::new();
+        for name in ["x", "y", "z"].iter() {
+            assert!(heap.push(7, Opaque(name)).is_ok());
+        }
+
+        let mut names: Vec<&str> = std::iter::from_fn(|| heap.pop()).map(|(_, v)| v.0).collect();
+        names.sort();
+        assert_eq!(names, vec!["x", "y", "z"])
+    }
+}
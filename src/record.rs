@@ -0,0 +1,185 @@
+use crate::fixed::{Full, StaticMinHeap};
+
+/// One call on a `Recorder`, with its arguments.
+///
+/// A trace's `Debug` output, such as `[Push(3), Push(1), Pop]`, is also
+/// the Rust code that builds it, so it can be pasted straight from a bug
+/// report into a test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op<T> {
+    /// `push(value)`, whether or not the heap had room
+    Push(T),
+    /// `pop()`
+    Pop,
+    /// `pop_if_le(&bound)`
+    PopIfLe(T),
+    /// `clear()`
+    Clear,
+}
+
+/// A `StaticMinHeap` that logs every call that changes it, so the state it
+/// ends up in can be rebuilt later with `replay`.
+/// # Example
+/// ```
+/// use min_heap::record::{self, Op, Recorder};
+///
+/// let mut heap = Recorder::<i32, 4>::new();
+/// heap.push(3).unwrap();
+/// heap.push(1).unwrap();
+/// heap.pop();
+///
+/// assert_eq!(heap.trace(), &[Op::Push(3), Op::Push(1), Op::Pop]);
+/// let rebuilt = record::replay::<i32, 4>(heap.trace());
+/// assert_eq!(&rebuilt, heap.heap())
+/// ```
+pub struct Recorder<T, const N: usize> {
+    heap: StaticMinHeap<T, N>,
+    trace: Vec<Op<T>>,
+}
+
+impl<T, const N: usize> Recorder<T, N> {
+    /// creates an empty heap with an empty trace
+    pub const fn new() -> Self {
+        Recorder {
+            heap: StaticMinHeap::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// returns the heap, for reading
+    pub fn heap(&self) -> &StaticMinHeap<T, N> {
+        &self.heap
+    }
+
+    /// returns every call recorded so far, oldest first
+    pub fn trace(&self) -> &[Op<T>] {
+        &self.trace
+    }
+
+    /// gives up the heap and returns the trace
+    pub fn into_trace(self) -> Vec<Op<T>> {
+        self.trace
+    }
+
+    /// removes every value from the heap
+    pub fn clear(&mut self) {
+        self.trace.push(Op::Clear);
+        self.heap.clear()
+    }
+}
+
+impl<T: Ord + Clone, const N: usize> Recorder<T, N> {
+    /// adds a value to the heap, or gives it back in `Err(Full)` if the
+    /// heap has no room left; the call is recorded either way
+    pub fn push(&mut self, value: T) -> Result<(), Full<T>> {
+        self.trace.push(Op::Push(value.clone()));
+        self.heap.push(value)
+    }
+
+    /// removes and returns the smallest value, or `None` if the heap is empty
+    pub fn pop(&mut self) -> Option<T> {
+        self.trace.push(Op::Pop);
+        self.heap.pop()
+    }
+
+    /// removes and returns the smallest value only if it is no greater
+    /// than `bound`
+    pub fn pop_if_le(&mut self, bound: &T) -> Option<T> {
+        self.trace.push(Op::PopIfLe(bound.clone()));
+        self.heap.pop_if_le(bound)
+    }
+}
+
+impl<T, const N: usize> Default for Recorder<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// rebuilds a heap by running the calls in `trace` on an empty one, in
+/// order
+///
+/// Replaying a prefix of a trace gives the heap as it was after that many
+/// calls, so a failing trace can be stepped through one call at a time.
+/// # Example
+/// ```
+/// use min_heap::record::{replay, Op};
+///
+/// let trace = [Op::Push(5), Op::Push(2), Op::PopIfLe(4), Op::Push(7)];
+/// let heap = replay::<i32, 4>(&trace[..3]);
+/// assert_eq!(heap.peek(), Some(&5))
+/// ```
+pub fn replay<T: Ord + Clone, const N: usize>(trace: &[Op<T>]) -> StaticMinHeap<T, N> {
+    let mut heap = StaticMinHeap::new();
+    for op in trace {
+        // results are dropped: replaying only has to reach the same state
+        match op {
+            Op::Push(value) => {
+                let _ = heap.push(value.clone());
+            }
+            Op::Pop => {
+                heap.pop();
+            }
+            Op::PopIfLe(bound) => {
+                heap.pop_if_le(bound);
+            }
+            Op::Clear => heap.clear(),
+        }
+    }
+    heap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slice::is_heap;
+
+    #[test]
+    fn records_every_call() {
+        let mut heap = Recorder::<i32, 2>::new();
+        heap.push(4).unwrap();
+        heap.push(2).unwrap();
+        assert!(heap.push(9).is_err());
+        assert_eq!(heap.pop_if_le(&1), None);
+        assert_eq!(heap.pop(), Some(2));
+        heap.clear();
+        heap.push(6).unwrap();
+
+        assert_eq!(heap.into_trace(), vec![
+            Op::Push(4),
+            Op::Push(2),
+            Op::Push(9),
+            Op::PopIfLe(1),
+            Op::Pop,
+            Op::Clear,
+            Op::Push(6),
+        ])
+    }
+
+    // every prefix of the trace must rebuild the heap as it was then
+    #[test]
+    fn replay_matches_each_step() {
+        let mut heap = Recorder::<i32, 8>::new();
+        let mut snapshots = vec![heap.heap().clone()];
+        for i in 0..40 {
+            match i % 5 {
+                0 | 1 | 3 => {
+                    let _ = heap.push((i * 7) % 11);
+                }
+                2 => {
+                    heap.pop();
+                }
+                _ => {
+                    heap.pop_if_le(&5);
+                }
+            }
+            snapshots.push(heap.heap().clone());
+        }
+
+        for (step, snapshot) in snapshots.iter().enumerate() {
+            let rebuilt = replay::<i32, 8>(&heap.trace()[..step]);
+            assert!(is_heap(rebuilt.as_slice()));
+            assert_eq!(rebuilt.as_slice(), snapshot.as_slice());
+        }
+    }
+}
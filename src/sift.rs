@@ -0,0 +1,115 @@
+// sift helpers for heaps stored in the implicit array layout, where the
+// children of index `i` are at `2 * i + 1` and `2 * i + 2`.
+//
+// `above(a, b)` decides the ordering: it returns true when `a` belongs
+// higher up the heap than `b`. For a min-heap that is `a < b`.
+
+use crate::index::{left_child_of, parent_of, right_child_of};
+
+/// moves the element at `i` up until its parent is not below it
+pub(crate) fn sift_up<T, F: Fn(&T, &T) -> bool>(data: &mut [T], mut i: usize, above: &F) {
+    let mut swaps = 0;
+    while let Some(parent) = parent_of(i) {
+        if !above(&data[i], &data[parent]) {
+            break;
+        }
+        data.swap(i, parent);
+        i = parent;
+        swaps += 1;
+    }
+    check_swaps(swaps, data.len());
+}
+
+/// moves the element at `i` down until neither child belongs above it
+pub(crate) fn sift_down<T, F: Fn(&T, &T) -> bool>(data: &mut [T], mut i: usize, above: &F) {
+    let mut swaps = 0;
+    loop {
+        let left = left_child_of(i);
+        if left >= data.len() {
+            break;
+        }
+
+        // pick whichever child should be higher up
+        let right = right_child_of(i);
+        let child = if right < data.len() && above(&data[right], &data[left]) {
+            right
+        } else {
+            left
+        };
+
+        if !above(&data[child], &data[i]) {
+            break;
+        }
+        data.swap(i, child);
+        i = child;
+        swaps += 1;
+    }
+    check_swaps(swaps, data.len());
+}
+
+/// turns the whole slice into a heap, bottom-up
+pub(crate) fn heapify<T, F: Fn(&T, &T) -> bool>(data: &mut [T], above: &F) {
+    for i in (0..data.len() / 2).rev() {
+        sift_down(data, i, above);
+    }
+}
+
+// with `sift-checks`, debug builds assert that a single sift never makes
+// more swaps than the tree is deep, which would mean a sift has
+// regressed to walking more than one path
+#[cfg(feature = "sift-checks")]
+fn check_swaps(swaps: usize, len: usize) {
+    debug_assert!(
+        swaps <= max_swaps(len),
+        "sift made {} swaps in a heap of {} elements",
+        swaps,
+        len
+    );
+}
+
+#[cfg(not(feature = "sift-checks"))]
+#[inline(always)]
+fn check_swaps(_swaps: usize, _len: usize) {}
+
+// ceil(log2(len)) plus one swap of slack
+#[cfg(feature = "sift-checks")]
+fn max_swaps(len: usize) -> usize {
+    len.next_power_of_two().trailing_zeros() as usize + 1
+}
+
+#[cfg(all(test, feature = "sift-checks", debug_assertions))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_swaps_grows_with_depth() {
+        assert_eq!(max_swaps(1), 1);
+        assert_eq!(max_swaps(2), 2);
+        assert_eq!(max_swaps(7), 4);
+        assert_eq!(max_swaps(8), 4);
+        assert_eq!(max_swaps(9), 5)
+    }
+
+    // the worst cases, a new minimum sifted up from the last leaf and a
+    // large value sifted down from the root, stay inside the bound
+    #[test]
+    fn sifts_within_bound() {
+        let less = |a: &i32, b: &i32| a < b;
+        let mut data: Vec<i32> = (0..1000).rev().collect();
+        heapify(&mut data, &less);
+        for i in 0..data.len() {
+            sift_up(&mut data, i, &less);
+        }
+        data.push(-1);
+        let last = data.len() - 1;
+        sift_up(&mut data, last, &less);
+        data.swap(0, last);
+        sift_down(&mut data[..last], 0, &less);
+    }
+
+    #[test]
+    #[should_panic(expected = "sift made 5 swaps")]
+    fn too_many_swaps_panic() {
+        check_swaps(5, 8);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::index::parent_of;
+use crate::sift::{heapify, sift_down, sift_up};
+
+/// C++-style heap algorithms on slices, so a heap can be kept in data the
+/// caller already owns.
+///
+/// Unlike C++, which builds max-heaps, these keep a min-heap: the smallest
+/// value is at index 0 and the children of index `i` are at `2 * i + 1` and
+/// `2 * i + 2`.
+/// # Example
+/// ```
+/// use min_heap::slice::HeapSlice;
+///
+/// let mut values = vec![5, 2, 8, 1];
+/// values.make_heap();
+/// assert_eq!(values[0], 1);
+///
+/// values.push(0);
+/// values.push_heap();
+/// values.pop_heap();
+/// assert_eq!(values.pop(), Some(0));
+/// assert!(values.is_heap())
+/// ```
+pub trait HeapSlice {
+    /// rearranges the slice into a heap
+    fn make_heap(&mut self);
+
+    /// adds the last value to the heap formed by the rest of the slice
+    fn push_heap(&mut self);
+
+    /// moves the smallest value to the end of the slice, keeping the rest
+    /// a heap
+    fn pop_heap(&mut self);
+
+    /// turns a heap into a sorted slice by popping it repeatedly, which
+    /// leaves the values in descending order
+    fn sort_heap(&mut self);
+
+    /// returns true if the slice is a heap
+    fn is_heap(&self) -> bool;
+}
+
+impl<T: Ord> HeapSlice for [T] {
+    fn make_heap(&mut self) {
+        heapify(self, &less);
+    }
+
+    fn push_heap(&mut self) {
+        if let Some(last) = self.len().checked_sub(1) {
+            sift_up(self, last, &less);
+        }
+    }
+
+    fn pop_heap(&mut self) {
+        if let Some(last) = self.len().checked_sub(1) {
+            self.swap(0, last);
+            sift_down(&mut self[..last], 0, &less);
+        }
+    }
+
+    fn sort_heap(&mut self) {
+        for end in (1..=self.len()).rev() {
+            self[..end].pop_heap();
+        }
+    }
+
+    fn is_heap(&self) -> bool {
+        is_heap(self)
+    }
+}
+
+/// returns true if every value in the slice is no smaller than its parent
+/// in the implicit array layout, so index 0 holds the smallest value
+/// # Example
+/// ```
+/// assert!(min_heap::slice::is_heap(&[1, 3, 2, 5, 4]));
+/// assert!(!min_heap::slice::is_heap(&[3, 1, 2]))
+/// ```
+pub fn is_heap<T: Ord>(slice: &[T]) -> bool {
+    is_heap_until(slice) == slice.len()
+}
+
+/// returns the length of the longest prefix of the slice that is a heap,
+/// which is the index of the first value smaller than its parent, or the
+/// slice's length if there is none
+/// # Example
+/// ```
+/// assert_eq!(min_heap::slice::is_heap_until(&[1, 3, 2, 0, 4]), 3);
+/// assert_eq!(min_heap::slice::is_heap_until(&[1, 3, 2]), 3)
+/// ```
+pub fn is_heap_until<T: Ord>(slice: &[T]) -> usize {
+    (1..slice.len())
+        .find(|&i| {
+            let parent = parent_of(i).expect("only the root has no parent");
+            slice[i] < slice[parent]
+        })
+        .unwrap_or(slice.len())
+}
+
+/// sorts the `k` smallest values of the slice into ascending order at its
+/// front, leaving the rest of the slice in an unspecified order
+///
+/// Like C++'s `std::partial_sort`, this keeps a heap of `k` values in the
+/// front of the slice, running in O(n log k) time without allocating. A `k`
+/// larger than the slice sorts the whole slice.
+/// # Example
+/// ```
+/// let mut values = [9, 4, 7, 1, 8, 2, 6];
+/// min_heap::slice::partial_sort(&mut values, 3);
+/// assert_eq!(values[..3], [1, 2, 4])
+/// ```
+pub fn partial_sort<T: Ord>(slice: &mut [T], k: usize) {
+    let k = k.min(slice.len());
+    if k == 0 {
+        return;
+    }
+
+    keep_smallest(slice, k);
+    sort_max_heap(&mut slice[..k]);
+}
+
+/// sorts the slice into ascending order using heapsort
+///
+/// The sort works entirely within the slice, without allocating, in
+/// O(n log n) time, so it is available in `no_std` builds. It is not
+/// stable.
+/// # Example
+/// ```
+/// let mut values = [5, 2, 9, 1, 5, 6];
+/// min_heap::slice::heapsort_in_place(&mut values);
+/// assert_eq!(values, [1, 2, 5, 5, 6, 9])
+/// ```
+pub fn heapsort_in_place<T: Ord>(slice: &mut [T]) {
+    heapify(slice, &greater);
+    sort_max_heap(slice);
+}
+
+/// returns the `n`th smallest value of the slice (counting from 0), and
+/// moves it to index `n` with smaller or equal values before it and larger
+/// or equal values after it
+///
+/// This keeps a heap of the `n + 1` smallest values in the front of the
+/// slice, running in O(len log n) time without allocating.
+///
+/// # Panics
+/// Panics if `n` is not less than the length of the slice.
+/// # Example
+/// ```
+/// let mut values = [9, 4, 7, 1, 8, 2, 6];
+/// let median = *min_heap::slice::select_nth(&mut values, 3);
+/// assert_eq!(median, 6);
+/// assert!(values[..3].iter().all(|v| *v <= 6));
+/// assert!(values[4..].iter().all(|v| *v >= 6))
+/// ```
+pub fn select_nth<T: Ord>(slice: &mut [T], n: usize) -> &mut T {
+    assert!(n < slice.len(), "select_nth index {} out of range for slice of length {}", n, slice.len());
+
+    keep_smallest(slice, n + 1);
+
+    // the root is now the largest of the n + 1 smallest, which is the nth
+    slice.swap(0, n);
+    &mut slice[n]
+}
+
+// gathers the k smallest values into a max-heap at the front of the
+// slice, so the largest of them ends up at index 0
+fn keep_smallest<T: Ord>(slice: &mut [T], k: usize) {
+    heapify(&mut slice[..k], &greater);
+
+    for i in k..slice.len() {
+        if slice[i] < slice[0] {
+            slice.swap(0, i);
+            sift_down(&mut slice[..k], 0, &greater);
+        }
+    }
+}
+
+// sorts a max-heap into ascending order by repeatedly moving its largest
+// value behind the shrinking heap
+fn sort_max_heap<T: Ord>(heap: &mut [T]) {
+    for end in (1..heap.len()).rev() {
+        heap.swap(0, end);
+        sift_down(&mut heap[..end], 0, &greater);
+    }
+}
+
+fn less<T: Ord>(a: &T, b: &T) -> bool {
+    a < b
+}
+
+fn greater<T: Ord>(a: &T, b: &T) -> bool {
+    a > b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_heap_and_is_heap() {
+        let mut values = [9, 3, 7, 1, 8, 2, 6, 5];
+        assert!(!values.is_heap());
+
+        values.make_heap();
+        assert!(values.is_heap());
+        assert_eq!(values[0], 1)
+    }
+
+    #[test]
+    fn is_heap_until_first_violation() {
+        assert_eq!(is_heap_until::<i32>(&[]), 0);
+        assert_eq!(is_heap_until(&[4]), 1);
+        assert_eq!(is_heap_until(&[1, 1, 1]), 3);
+        assert_eq!(is_heap_until(&[2, 1]), 1);
+        assert_eq!(is_heap_until(&[1, 4, 2, 5, 3, 6]), 4);
+        assert!(!is_heap(&[1, 4, 2, 5, 3, 6]))
+    }
+
+    #[test]
+    fn push_and_pop_heap() {
+        let mut values: Vec<i32> = vec![];
+        for value in [4, 8, 1, 6, 3].iter() {
+            values.push(*value);
+            values.push_heap();
+            assert!(values.is_heap());
+        }
+
+        let mut popped = vec![];
+        while !values.is_empty() {
+            values.pop_heap();
+            popped.push(values.pop().unwrap());
+            assert!(values.is_heap());
+        }
+        assert_eq!(popped, vec![1, 3, 4, 6, 8])
+    }
+
+    #[test]
+    fn sort_heap_descending() {
+        let mut values = vec![4, 8, 1, 6, 3, 1];
+        values.make_heap();
+        values.sort_heap();
+        assert_eq!(values, vec![8, 6, 4, 3, 1, 1])
+    }
+
+    // the algorithms must leave empty and single-value slices alone
+    #[test]
+    fn heap_slice_tiny() {
+        let mut empty: Vec<i32> = vec![];
+        empty.make_heap();
+        empty.push_heap();
+        empty.pop_heap();
+        empty.sort_heap();
+        assert!(empty.is_heap());
+
+        let mut one = vec![7];
+        one.pop_heap();
+        one.sort_heap();
+        assert_eq!(one, vec![7])
+    }
+
+    #[test]
+    fn heapsort_in_place_sorts() {
+        let mut values = vec![5, 3, 9, 1, 7, 2, 8, 6, 4, 3, 0];
+        let mut expected = values.clone();
+        expected.sort();
+
+        heapsort_in_place(&mut values);
+        assert_eq!(values, expected)
+    }
+
+    #[test]
+    fn heapsort_in_place_tiny() {
+        let mut empty: Vec<i32> = vec![];
+        heapsort_in_place(&mut empty);
+        assert!(empty.is_empty());
+
+        let mut two = vec![2, 1];
+        heapsort_in_place(&mut two);
+        assert_eq!(two, vec![1, 2])
+    }
+
+    #[test]
+    fn partial_sort_front() {
+        let mut values = vec![5, 3, 9, 1, 7, 2, 8, 6, 4];
+        partial_sort(&mut values, 4);
+        assert_eq!(values[..4], [1, 2, 3, 4]);
+
+        // the rest is unordered but nothing went missing
+        let mut rest = values[4..].to_vec();
+        rest.sort();
+        assert_eq!(rest, vec![5, 6, 7, 8, 9])
+    }
+
+    #[test]
+    fn partial_sort_whole_slice() {
+        let mut values = vec![3, 1, 2, 1];
+        partial_sort(&mut values, 10);
+        assert_eq!(values, vec![1, 1, 2, 3])
+    }
+
+    #[test]
+    fn select_nth_partitions() {
+        let original = vec![5, 3, 9, 1, 7, 2, 8, 6, 4, 3];
+        let mut sorted = original.clone();
+        sorted.sort();
+
+        for n in 0..original.len() {
+            let mut values = original.clone();
+            let nth = *select_nth(&mut values, n);
+            assert_eq!(nth, sorted[n]);
+            assert_eq!(values[n], nth);
+            assert!(values[..n].iter().all(|v| *v <= nth));
+            assert!(values[n + 1..].iter().all(|v| *v >= nth));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_nth_out_of_range() {
+        let mut values = vec![1, 2, 3];
+        select_nth(&mut values, 3);
+    }
+
+    #[test]
+    fn partial_sort_nothing() {
+        let mut values = vec![3, 1, 2];
+        partial_sort(&mut values, 0);
+        assert_eq!(values, vec![3, 1, 2]);
+
+        let mut empty: Vec<i32> = vec![];
+        partial_sort(&mut empty, 2);
+        assert!(empty.is_empty())
+    }
+}
@@ -0,0 +1,70 @@
+// thread-safe stand-ins for the `Rc`, `Weak`, `Cell<i32>` and `RefCell`
+// that the node tree uses by default, switched in by the `sync-nodes`
+// feature. They offer the same methods so the node module reads the same
+// either way.
+
+use std::fmt;
+use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub(crate) use std::sync::{Arc as Rc, Weak};
+
+/// a `RwLock` with `RefCell`'s `borrow`/`borrow_mut` interface
+pub(crate) struct RefCell<T>(RwLock<T>);
+
+impl<T> RefCell<T> {
+    pub(crate) fn new(value: T) -> RefCell<T> {
+        RefCell(RwLock::new(value))
+    }
+
+    pub(crate) fn borrow(&self) -> RwLockReadGuard<'_, T> {
+        self.0.read().expect("node lock poisoned")
+    }
+
+    pub(crate) fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+        self.0.write().expect("node lock poisoned")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.borrow(), f)
+    }
+}
+
+/// a `Mutex<i32>` with the `Cell<i32>` methods the nodes use
+///
+/// A lock rather than an atomic, so that `swap` can hold both values at
+/// once and two swaps sharing a node can't interleave.
+pub(crate) struct ValueCell(Mutex<i32>);
+
+impl ValueCell {
+    pub(crate) fn new(value: i32) -> ValueCell {
+        ValueCell(Mutex::new(value))
+    }
+
+    pub(crate) fn get(&self) -> i32 {
+        *self.0.lock().expect("node lock poisoned")
+    }
+
+    // the two locks are always taken in address order, so swaps running
+    // at the same time on overlapping pairs can't deadlock
+    pub(crate) fn swap(&self, other: &ValueCell) {
+        if std::ptr::eq(self, other) {
+            return;
+        }
+        let (first, second) = if (self as *const ValueCell) < (other as *const ValueCell) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut first = first.0.lock().expect("node lock poisoned");
+        let mut second = second.0.lock().expect("node lock poisoned");
+        std::mem::swap(&mut *first, &mut *second);
+    }
+}
+
+impl fmt::Debug for ValueCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
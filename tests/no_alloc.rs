@@ -0,0 +1,64 @@
+// StaticMinHeap's hot path must never touch the allocator. This lives in
+// its own test binary because the counting allocator replaces the global
+// one for every test in the binary.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use min_heap::fixed::StaticMinHeap;
+
+// counts allocator calls made on the current thread, so tests running
+// in parallel don't see each other's allocations
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATOR_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_call() {
+    // a thread being torn down has no counter left
+    let _ = ALLOCATOR_CALLS.try_with(|calls| calls.set(calls.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_call();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        count_call();
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_call();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+fn allocator_calls() -> usize {
+    ALLOCATOR_CALLS.with(|calls| calls.get())
+}
+
+#[test]
+fn hot_path_never_allocates() {
+    let mut heap = StaticMinHeap::<u64, 64>::new();
+    let before = allocator_calls();
+
+    for round in 0..100u64 {
+        for i in 0..64 {
+            heap.push((i * 37 + round) % 101).unwrap();
+        }
+        assert!(heap.push(0).is_err());
+        let _ = heap.peek();
+        let _ = heap.pop_if_le(&50);
+        for _ in heap.pop_batch(10, |v| *v < 90) {}
+        while heap.pop().is_some() {}
+    }
+
+    assert_eq!(allocator_calls(), before)
+}